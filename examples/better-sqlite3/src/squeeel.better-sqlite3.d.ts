@@ -1,10 +1,28 @@
 type Tables = {
     "a": {
-        "i"?: number | undefined;
-        "f"?: number | undefined;
-        "s"?: null | undefined;
-        "bf"?: null | undefined;
-        "b"?: unknown | undefined;
+        "i": number | null;
+        "f": number | null;
+        "s": number | string | null;
+        "bf": number | string | null;
+        "b": number | null;
+    };
+};
+type Insertable = {
+    "a": {
+        "i"?: number | bigint | null;
+        "f"?: number | null;
+        "s"?: number | bigint | string | null;
+        "bf"?: number | bigint | string | null;
+        "b"?: number | bigint | null;
+    };
+};
+type Updatable = {
+    "a": {
+        "i"?: number | bigint | null;
+        "f"?: number | null;
+        "s"?: number | bigint | string | null;
+        "bf"?: number | bigint | string | null;
+        "b"?: number | bigint | null;
     };
 };
 type Queries = {
@@ -18,41 +36,118 @@ type Queries = {
 				true AS bool
         	`]: {
         "returnType": {
-            "n"?: null | undefined;
+            "n": number | string | Buffer | null;
             "int": number;
             "float": number;
             "string": string;
             "buffer": Buffer;
             "bool": number;
         };
+        "rawReturnType": [number | string | Buffer | null, number, number, string, Buffer, number];
+        "reader": true;
+        "expandReturnType": {
+            "$": {
+                "n": number | string | Buffer | null;
+                "int": number;
+                "float": number;
+                "string": string;
+                "buffer": Buffer;
+                "bool": number;
+            };
+        };
+        "safeIntegers": {
+            "returnType": {
+                "n": number | bigint | string | Buffer | null;
+                "int": bigint;
+                "float": number;
+                "string": string;
+                "buffer": Buffer;
+                "bool": bigint;
+            };
+            "rawReturnType": [number | bigint | string | Buffer | null, bigint, number, string, Buffer, bigint];
+            "expandReturnType": {
+                "$": {
+                    "n": number | bigint | string | Buffer | null;
+                    "int": bigint;
+                    "float": number;
+                    "string": string;
+                    "buffer": Buffer;
+                    "bool": bigint;
+                };
+            };
+        };
         "args": never;
     };
     [`SELECT ?`]: {
         "returnType": {
-            "?"?: null | undefined;
+            "?": number | string | Buffer | null;
+        };
+        "rawReturnType": [number | string | Buffer | null];
+        "reader": true;
+        "expandReturnType": {
+            "$": {
+                "?": number | string | Buffer | null;
+            };
         };
-        "args": [unknown];
+        "safeIntegers": {
+            "returnType": {
+                "?": number | bigint | string | Buffer | null;
+            };
+            "rawReturnType": [number | bigint | string | Buffer | null];
+            "expandReturnType": {
+                "$": {
+                    "?": number | bigint | string | Buffer | null;
+                };
+            };
+        };
+        "args": [$1: unknown];
     };
 };
 declare module 'better-sqlite3' {
     type VariableArgFunction = (...params: any[]) => unknown;
     type ArgumentTypes<F extends VariableArgFunction> = F extends (...args: infer A) => unknown ? A : never;
     type ElementOf<T> = T extends Array<infer E> ? E : T;
-    interface Statement<BindParameters extends unknown[], Result = unknown> {
+    type RowTypes = {
+        returnType: unknown;
+        rawReturnType: unknown[];
+        expandReturnType: unknown;
+    };
+    type QueryRowTypes = RowTypes & {
+        reader: boolean;
+        safeIntegers: RowTypes;
+    };
+    type RowMode = "object" | "pluck" | "raw" | "expand";
+    type ModeRow<Types extends RowTypes, Mode extends RowMode> = Mode extends "pluck" ? Types["rawReturnType"][0] : Mode extends "raw" ? Types["rawReturnType"] : Mode extends "expand" ? Types["expandReturnType"] : Types["returnType"];
+    type StatementRow<Types extends QueryRowTypes, Mode extends RowMode, SafeIntegers extends boolean> = ModeRow<SafeIntegers extends true ? Types["safeIntegers"] : Types, Mode>;
+    interface Statement<BindParameters extends unknown[], Types extends QueryRowTypes = QueryRowTypes, Mode extends RowMode = "object", SafeIntegers extends boolean = false> {
         database: Database;
         source: string;
-        reader: boolean;
+        reader: Types["reader"];
         readonly: boolean;
         busy: boolean;
         run(...params: BindParameters): Database.RunResult;
-        get(...params: BindParameters): Result | undefined;
-        all(...params: BindParameters): Result[];
-        iterate(...params: BindParameters): IterableIterator<Result>;
-        pluck(toggleState?: boolean): this;
-        expand(toggleState?: boolean): this;
-        raw(toggleState?: boolean): this;
+        get(...params: BindParameters): StatementRow<Types, Mode, SafeIntegers> | undefined;
+        all(...params: BindParameters): StatementRow<Types, Mode, SafeIntegers>[];
+        iterate(...params: BindParameters): IterableIterator<StatementRow<Types, Mode, SafeIntegers>>;
+        pluck(toggleState?: true): Statement<BindParameters, Types, "pluck", SafeIntegers>;
+        pluck(toggleState: false): Statement<BindParameters, Types, Mode extends "pluck" ? "object" : Mode, SafeIntegers>;
+        expand(toggleState?: true): Statement<BindParameters, Types, "expand", SafeIntegers>;
+        expand(toggleState: false): Statement<BindParameters, Types, Mode extends "expand" ? "object" : Mode, SafeIntegers>;
+        raw(toggleState?: true): Statement<BindParameters, Types, "raw", SafeIntegers>;
+        raw(toggleState: false): Statement<BindParameters, Types, Mode extends "raw" ? "object" : Mode, SafeIntegers>;
         bind(...params: BindParameters): this;
         columns(): ColumnDefinition[];
+        safeIntegers(toggleState?: true): Statement<BindParameters, Types, Mode, true>;
+        safeIntegers(toggleState: false): Statement<BindParameters, Types, Mode, false>;
+    }
+    interface RunStatement<BindParameters extends unknown[]> {
+        database: Database;
+        source: string;
+        reader: false;
+        readonly: boolean;
+        busy: boolean;
+        run(...params: BindParameters): Database.RunResult;
+        bind(...params: BindParameters): this;
         safeIntegers(toggleState?: boolean): this;
     }
     interface ColumnDefinition {
@@ -82,7 +177,7 @@ declare module 'better-sqlite3' {
         name: string;
         open: boolean;
         inTransaction: boolean;
-        prepare<T extends string>(source: T): Statement<T extends keyof Queries ? Queries[T]["args"] extends never ? [] : Queries[T]["args"] : unknown[], T extends keyof Queries ? Queries[T]["returnType"] : unknown>;
+        prepare<T extends string>(source: T): T extends keyof Queries ? Queries[T]["reader"] extends true ? Statement<Queries[T]["args"] extends never ? [] : Queries[T]["args"], Queries[T]> : RunStatement<Queries[T]["args"] extends never ? [] : Queries[T]["args"]> : Statement<unknown[]>;
         transaction<F extends VariableArgFunction>(fn: F): Transaction<F>;
         exec(source: string): this;
         pragma(source: string, options?: Database.PragmaOptions): unknown;
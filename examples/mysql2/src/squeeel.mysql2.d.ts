@@ -2,29 +2,84 @@ import type mysql from "mysql2/promise";
 type JsonValue = string | number | boolean | null | {
     [Key in string]?: JsonValue;
 } | JsonValue[];
+type Point = {
+    x: number;
+    y: number;
+};
+type Geometry = Point | Point[] | Point[][] | Point[][][] | Geometry[];
 type Tables = {
     "a": {
         "id": number;
-        "char_col"?: string | undefined;
-        "varchar_col"?: string | undefined;
-        "binary_col"?: Buffer | undefined;
-        "varbinary_col"?: Buffer | undefined;
-        "text_col"?: string | undefined;
-        "blob_col"?: Buffer | undefined;
-        "tinyint_col"?: number | undefined;
-        "smallint_col"?: number | undefined;
-        "mediumint_col"?: number | undefined;
-        "int_col"?: number | undefined;
-        "bigint_col"?: number | undefined;
-        "float_col"?: number | undefined;
-        "double_col"?: number | undefined;
-        "decimal_col"?: string | undefined;
-        "date_col"?: Date | undefined;
-        "time_col"?: string | undefined;
-        "datetime_col"?: Date | undefined;
-        "timestamp_col"?: Date | undefined;
-        "bool_col"?: number | undefined;
-        "json_col"?: JsonValue | undefined;
+        "char_col": string | null;
+        "varchar_col": string | null;
+        "binary_col": Buffer | null;
+        "varbinary_col": Buffer | null;
+        "text_col": string | null;
+        "blob_col": Buffer | null;
+        "tinyint_col": number | null;
+        "smallint_col": number | null;
+        "mediumint_col": number | null;
+        "int_col": number | null;
+        "bigint_col": number | null;
+        "float_col": number | null;
+        "double_col": number | null;
+        "decimal_col": string | null;
+        "date_col": Date | null;
+        "time_col": string | null;
+        "datetime_col": Date | null;
+        "timestamp_col": Date | null;
+        "bool_col": number | null;
+        "json_col": JsonValue | null;
+    };
+};
+type Insertable = {
+    "a": {
+        "id"?: number;
+        "char_col"?: string | null;
+        "varchar_col"?: string | null;
+        "binary_col"?: Buffer | string | null;
+        "varbinary_col"?: Buffer | string | null;
+        "text_col"?: string | null;
+        "blob_col"?: Buffer | string | null;
+        "tinyint_col"?: number | null;
+        "smallint_col"?: number | null;
+        "mediumint_col"?: number | null;
+        "int_col"?: number | null;
+        "bigint_col"?: number | bigint | string | null;
+        "float_col"?: number | null;
+        "double_col"?: number | null;
+        "decimal_col"?: number | string | null;
+        "date_col"?: Date | string | null;
+        "time_col"?: string | null;
+        "datetime_col"?: Date | string | null;
+        "timestamp_col"?: Date | string | null;
+        "bool_col"?: boolean | number | null;
+        "json_col"?: JsonValue | null;
+    };
+};
+type Updatable = {
+    "a": {
+        "id"?: number;
+        "char_col"?: string | null;
+        "varchar_col"?: string | null;
+        "binary_col"?: Buffer | string | null;
+        "varbinary_col"?: Buffer | string | null;
+        "text_col"?: string | null;
+        "blob_col"?: Buffer | string | null;
+        "tinyint_col"?: number | null;
+        "smallint_col"?: number | null;
+        "mediumint_col"?: number | null;
+        "int_col"?: number | null;
+        "bigint_col"?: number | bigint | string | null;
+        "float_col"?: number | null;
+        "double_col"?: number | null;
+        "decimal_col"?: number | string | null;
+        "date_col"?: Date | string | null;
+        "time_col"?: string | null;
+        "datetime_col"?: Date | string | null;
+        "timestamp_col"?: Date | string | null;
+        "bool_col"?: boolean | number | null;
+        "json_col"?: JsonValue | null;
     };
 };
 type Queries = {
@@ -57,42 +112,53 @@ type Queries = {
 		FROM a;
 			`]: {
         "returnType": {
-            "char_col"?: string | undefined;
-            "varchar_col"?: string | undefined;
-            "bool_col"?: number | undefined;
-            "tinyint_col"?: number | undefined;
-            "smallint_col"?: number | undefined;
-            "mediumint_col"?: number | undefined;
-            "int_col"?: number | undefined;
-            "bigint_col"?: number | undefined;
-            "float_col"?: number | undefined;
-            "double_col"?: number | undefined;
-            "decimal_col"?: string | undefined;
-            "null_value"?: null | undefined;
-            "binary_col"?: Buffer | undefined;
-            "varbinary_col"?: Buffer | undefined;
-            "date_col"?: Date | undefined;
-            "time_col"?: string | undefined;
-            "datetime_col"?: Date | undefined;
-            "timestamp_col"?: Date | undefined;
-            "text_col"?: string | undefined;
-            "blob_col"?: Buffer | undefined;
-            "json_col"?: JsonValue | undefined;
-            "uuid_col"?: string | undefined;
-            "inet4_as_int_col"?: number | undefined;
+            "char_col": string | null;
+            "varchar_col": string | null;
+            "bool_col": number | null;
+            "tinyint_col": number | null;
+            "smallint_col": number | null;
+            "mediumint_col": number | null;
+            "int_col": number | null;
+            "bigint_col": number | null;
+            "float_col": number | null;
+            "double_col": number | null;
+            "decimal_col": string | null;
+            "null_value": null | null;
+            "binary_col": Buffer | null;
+            "varbinary_col": Buffer | null;
+            "date_col": Date | null;
+            "time_col": string | null;
+            "datetime_col": Date | null;
+            "timestamp_col": Date | null;
+            "text_col": string | null;
+            "blob_col": Buffer | null;
+            "json_col": JsonValue | null;
+            "uuid_col": string | null;
+            "inet4_as_int_col": number | null;
             "unsigned_int_col": number;
         };
+        "reader": true;
         "args": never;
     };
     [`SELECT ? as input`]: {
         "returnType": {
-            "input"?: string | undefined;
+            "input": string | null;
         };
-        "args": [unknown];
+        "reader": true;
+        "args": [$1: unknown];
     };
 };
+type TypedStatementResult<S> = S extends {
+    reader: true;
+    returnType: infer Row;
+} ? Row[] : mysql.ResultSetHeader;
+type TypedQueryResult<T extends string> = T extends keyof Queries ? Queries[T] extends {
+    statements: infer Statements;
+} ? {
+    [I in keyof Statements]: TypedStatementResult<Statements[I]>;
+} : TypedStatementResult<Queries[T]> : unknown;
 declare module "mysql2/promise" {
     export interface Connection {
-        query<T extends string>(...params: T extends keyof Queries ? Queries[T]["args"] extends never ? [sql: T] : [sql: T, values: Queries[T]["args"]] : [sql: T, values: any]): Promise<[T extends keyof Queries ? Queries[T]["returnType"][] : unknown, mysql.FieldPacket[]]>;
+        query<T extends string>(...params: T extends keyof Queries ? Queries[T]["args"] extends never ? [sql: T] : [sql: T, values: Queries[T]["args"]] : [sql: T, values: any]): Promise<[TypedQueryResult<T>, mysql.FieldPacket[]]>;
     }
 }
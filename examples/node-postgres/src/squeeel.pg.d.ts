@@ -2,35 +2,36 @@ import type pg from "pg";
 type JsonValue = string | number | boolean | null | {
     [Key in string]?: JsonValue;
 } | JsonValue[];
+export type MyEnum = "a" | "b" | "c";
 type Tables = {
     "a": {
         "id": number;
-        "b"?: boolean | undefined;
-        "si"?: number | undefined;
-        "i"?: number | undefined;
-        "bi"?: string | undefined;
-        "r"?: number | undefined;
-        "d"?: number | undefined;
-        "c"?: string | undefined;
-        "s"?: string | undefined;
-        "bt"?: string | undefined;
-        "vb"?: string | undefined;
-        "bta"?: Buffer | undefined;
-        "bx"?: string | undefined;
-        "pnt"?: {
+        "b": boolean | null;
+        "si": number | null;
+        "i": number | null;
+        "bi": string | null;
+        "r": number | null;
+        "d": number | null;
+        "c": string | null;
+        "s": string | null;
+        "bt": string | null;
+        "vb": string | null;
+        "bta": Buffer | null;
+        "bx": string | null;
+        "pnt": {
             "x": number;
             "y": number;
-        } | undefined;
-        "pth"?: string | undefined;
-        "plgn"?: string | undefined;
-        "ln"?: string | undefined;
-        "lsg"?: string | undefined;
-        "crcl"?: {
+        } | null;
+        "pth": string | null;
+        "plgn": string | null;
+        "ln": string | null;
+        "lsg": string | null;
+        "crcl": {
             "x": number;
             "y": number;
             "radius": number;
-        } | undefined;
-        "intvl"?: {
+        } | null;
+        "intvl": {
             "milliseconds"?: number;
             "seconds"?: number;
             "minutes"?: number;
@@ -38,17 +39,85 @@ type Tables = {
             "days"?: number;
             "months"?: number;
             "years"?: number;
-        } | undefined;
-        "jsn"?: JsonValue | undefined;
-        "uid"?: string | undefined;
-        "dt"?: Date | undefined;
-        "cdr"?: string | undefined;
-        "nt"?: string | undefined;
-        "mcddr"?: string | undefined;
-        "mcd8"?: string | undefined;
-        "num"?: string | undefined;
-        "nm"?: string | undefined;
-        "en"?: "a" | "b" | "c" | undefined;
+        } | null;
+        "jsn": JsonValue | null;
+        "uid": string | null;
+        "dt": Date | null;
+        "cdr": string | null;
+        "nt": string | null;
+        "mcddr": string | null;
+        "mcd8": string | null;
+        "num": string | null;
+        "nm": string | null;
+        "en": MyEnum | null;
+    };
+};
+type Insertable = {
+    "a": {
+        "id"?: number;
+        "b"?: boolean | null;
+        "si"?: number | null;
+        "i"?: number | null;
+        "bi"?: number | bigint | string | null;
+        "r"?: number | null;
+        "d"?: number | null;
+        "c"?: string | null;
+        "s"?: string | null;
+        "bt"?: string | null;
+        "vb"?: string | null;
+        "bta"?: Buffer | string | null;
+        "bx"?: string | null;
+        "pnt"?: string | null;
+        "pth"?: string | null;
+        "plgn"?: string | null;
+        "ln"?: string | null;
+        "lsg"?: string | null;
+        "crcl"?: string | null;
+        "intvl"?: string | null;
+        "jsn"?: JsonValue | null;
+        "uid"?: string | null;
+        "dt"?: Date | string | null;
+        "cdr"?: string | null;
+        "nt"?: string | null;
+        "mcddr"?: string | null;
+        "mcd8"?: string | null;
+        "num"?: number | string | null;
+        "nm"?: string | null;
+        "en"?: MyEnum | null;
+    };
+};
+type Updatable = {
+    "a": {
+        "id"?: number;
+        "b"?: boolean | null;
+        "si"?: number | null;
+        "i"?: number | null;
+        "bi"?: number | bigint | string | null;
+        "r"?: number | null;
+        "d"?: number | null;
+        "c"?: string | null;
+        "s"?: string | null;
+        "bt"?: string | null;
+        "vb"?: string | null;
+        "bta"?: Buffer | string | null;
+        "bx"?: string | null;
+        "pnt"?: string | null;
+        "pth"?: string | null;
+        "plgn"?: string | null;
+        "ln"?: string | null;
+        "lsg"?: string | null;
+        "crcl"?: string | null;
+        "intvl"?: string | null;
+        "jsn"?: JsonValue | null;
+        "uid"?: string | null;
+        "dt"?: Date | string | null;
+        "cdr"?: string | null;
+        "nt"?: string | null;
+        "mcddr"?: string | null;
+        "mcd8"?: string | null;
+        "num"?: number | string | null;
+        "nm"?: string | null;
+        "en"?: MyEnum | null;
     };
 };
 type Queries = {
@@ -65,7 +134,7 @@ type Queries = {
 			'hello' as string,
 			x'0010' as bit,
 			x'0010'::varbit as varbit,
-			'\xDEADBEEF'::bytea as bytea,
+			'ÞADBEEF'::bytea as bytea,
 			'(2,2),(0,0)'::box as box,
 			POINT(1.2, 123.1) as point,
 			'[(0,0),(1,1)]'::path as path,
@@ -87,33 +156,33 @@ type Queries = {
 			'a'::my_enum as enm
         `]: {
         "returnType": {
-            "null"?: string | undefined;
-            "bool"?: boolean | undefined;
-            "smallint"?: number | undefined;
-            "integer"?: number | undefined;
-            "bigint"?: string | undefined;
-            "real"?: number | undefined;
-            "double"?: number | undefined;
-            "char"?: string | undefined;
-            "string"?: string | undefined;
-            "bit"?: string | undefined;
-            "varbit"?: string | undefined;
-            "bytea"?: Buffer | undefined;
-            "box"?: string | undefined;
-            "point"?: {
+            "null": string | null;
+            "bool": boolean | null;
+            "smallint": number | null;
+            "integer": number | null;
+            "bigint": string | null;
+            "real": number | null;
+            "double": number | null;
+            "char": string | null;
+            "string": string | null;
+            "bit": string | null;
+            "varbit": string | null;
+            "bytea": Buffer | null;
+            "box": string | null;
+            "point": {
                 "x": number;
                 "y": number;
-            } | undefined;
-            "path"?: string | undefined;
-            "polygon"?: string | undefined;
-            "line"?: string | undefined;
-            "lseg"?: string | undefined;
-            "circle"?: {
+            } | null;
+            "path": string | null;
+            "polygon": string | null;
+            "line": string | null;
+            "lseg": string | null;
+            "circle": {
                 "x": number;
                 "y": number;
                 "radius": number;
-            } | undefined;
-            "interval"?: {
+            } | null;
+            "interval": {
                 "milliseconds"?: number;
                 "seconds"?: number;
                 "minutes"?: number;
@@ -121,31 +190,130 @@ type Queries = {
                 "days"?: number;
                 "months"?: number;
                 "years"?: number;
-            } | undefined;
-            "json"?: JsonValue | undefined;
-            "uuid"?: string | undefined;
-            "date"?: Date | undefined;
-            "cidr"?: string | undefined;
-            "inet"?: string | undefined;
-            "macaddr"?: string | undefined;
-            "macaddr8"?: string | undefined;
-            "numeric"?: string | undefined;
-            "name"?: string | undefined;
-            "oid"?: number | undefined;
-            "enm"?: "a" | "b" | "c" | undefined;
+            } | null;
+            "json": JsonValue | null;
+            "uuid": string | null;
+            "date": Date | null;
+            "cidr": string | null;
+            "inet": string | null;
+            "macaddr": string | null;
+            "macaddr8": string | null;
+            "numeric": string | null;
+            "name": string | null;
+            "oid": number | null;
+            "enm": MyEnum | null;
         };
+        "rawReturnType": [string | null, boolean | null, number | null, number | null, string | null, number | null, number | null, string | null, string | null, string | null, string | null, Buffer | null, string | null, {
+                "x": number;
+                "y": number;
+            } | null, string | null, string | null, string | null, string | null, {
+                "x": number;
+                "y": number;
+                "radius": number;
+            } | null, {
+                "milliseconds"?: number;
+                "seconds"?: number;
+                "minutes"?: number;
+                "hours"?: number;
+                "days"?: number;
+                "months"?: number;
+                "years"?: number;
+            } | null, JsonValue | null, string | null, Date | null, string | null, string | null, string | null, string | null, string | null, string | null, number | null, MyEnum | null];
+        "command": "SELECT";
         "args": never;
     };
     [`SELECT $1`]: {
         "returnType": {
-            "?column?"?: string | undefined;
+            "?column?": string | null;
         };
-        "args": [string];
+        "rawReturnType": [string | null];
+        "command": "SELECT";
+        "args": [$1: string];
     };
 };
+export type ResultsRow = {
+    "null": string | null;
+    "bool": boolean | null;
+    "smallint": number | null;
+    "integer": number | null;
+    "bigint": string | null;
+    "real": number | null;
+    "double": number | null;
+    "char": string | null;
+    "string": string | null;
+    "bit": string | null;
+    "varbit": string | null;
+    "bytea": Buffer | null;
+    "box": string | null;
+    "point": {
+        "x": number;
+        "y": number;
+    } | null;
+    "path": string | null;
+    "polygon": string | null;
+    "line": string | null;
+    "lseg": string | null;
+    "circle": {
+        "x": number;
+        "y": number;
+        "radius": number;
+    } | null;
+    "interval": {
+        "milliseconds"?: number;
+        "seconds"?: number;
+        "minutes"?: number;
+        "hours"?: number;
+        "days"?: number;
+        "months"?: number;
+        "years"?: number;
+    } | null;
+    "json": JsonValue | null;
+    "uuid": string | null;
+    "date": Date | null;
+    "cidr": string | null;
+    "inet": string | null;
+    "macaddr": string | null;
+    "macaddr8": string | null;
+    "numeric": string | null;
+    "name": string | null;
+    "oid": number | null;
+    "enm": MyEnum | null;
+};
+export type Results2Row = {
+    "?column?": string | null;
+};
+export type Results2Params = [$1: string];
+type TypedStatementResult<S, ArrayRows extends boolean> = S extends {
+    returnType: infer Row extends pg.QueryResultRow;
+    rawReturnType: infer RawRow extends unknown[];
+    command: infer Command;
+} ? (ArrayRows extends true ? pg.QueryArrayResult<RawRow> : pg.QueryResult<Row>) & {
+    command: Command;
+} : never;
+type TypedQueryResult<T extends string, ArrayRows extends boolean = false> = T extends keyof Queries ? Queries[T] extends {
+    statements: infer Statements;
+} ? {
+    [I in keyof Statements]: TypedStatementResult<Statements[I], ArrayRows>;
+} : TypedStatementResult<Queries[T], ArrayRows> : ArrayRows extends true ? pg.QueryArrayResult : pg.QueryResult<unknown>;
+type TypedQueryConfig<T extends string> = T extends keyof Queries ? Queries[T]["args"] extends never ? {
+    text: T;
+    values?: [];
+} : {
+    text: T;
+    values: Queries[T]["args"];
+} : {
+    text: T;
+    values?: any;
+};
 declare module "pg" {
     export interface ClientBase {
-        query<T extends string>(...params: T extends keyof Queries ? Queries[T]["args"] extends never ? [q: T, callback: (err: Error, result: pg.QueryResult<T extends keyof Queries ? Queries[T]["returnType"] : unknown>) => void] : [q: T, args: Queries[T]["args"], callback: (err: Error, result: pg.QueryResult<T extends keyof Queries ? Queries[T]["returnType"] : unknown>) => void] : [q: T, args: any, callback: (err: Error, result: pg.QueryResult<T extends keyof Queries ? Queries[T]["returnType"] : unknown>) => void]): void;
-        query<T extends string>(...params: T extends keyof Queries ? Queries[T]["args"] extends never ? [q: T] : [q: T, args: Queries[T]["args"]] : [q: T, args: any]): Promise<pg.QueryResult<T extends keyof Queries ? Queries[T]["returnType"] : unknown>>;
+        query<T extends string>(...params: T extends keyof Queries ? Queries[T]["args"] extends never ? [q: T, callback: (err: Error, result: TypedQueryResult<T>) => void] : [q: T, args: Queries[T]["args"], callback: (err: Error, result: TypedQueryResult<T>) => void] : [q: T, args: any, callback: (err: Error, result: TypedQueryResult<T>) => void]): void;
+        query<T extends string>(...params: T extends keyof Queries ? Queries[T]["args"] extends never ? [q: T] : [q: T, args: Queries[T]["args"]] : [q: T, args: any]): Promise<TypedQueryResult<T>>;
+        query<T extends string>(config: TypedQueryConfig<T> & {
+            rowMode: "array";
+        }): Promise<TypedQueryResult<T, true>>;
+        query<T extends string>(config: TypedQueryConfig<T> & {
+            rowMode?: undefined;
+        }): Promise<TypedQueryResult<T>>;
     }
 }
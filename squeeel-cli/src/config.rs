@@ -0,0 +1,207 @@
+use crate::Dialect;
+use crate::describe::ColumnOrigin;
+use crate::utils::ts_types::parse_ts_type;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use swc_ecma_ast::TsType;
+
+/// The name of the config file, looked for in the project root
+pub const CONFIG_FILE_NAME: &str = "squeeel.config.json";
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Config {
+    /// The dialect used by libs that work with any database, like knex and ORMs. This is normally detected
+    /// from the config of the lib itself, and only needs to be set if that fails.
+    pub dialect: Option<Dialect>,
+    /// TS types to use instead of the default ones, keyed by the name of the database type (like `int8`) or
+    /// its Postgres OID (like `"20"`). This is needed if the driver is configured with custom type parsers.
+    #[serde(default)]
+    pub types: HashMap<String, TypeOverride>,
+    /// TS types to use for specific table columns, keyed by `schema.table.column`, or `table.column` for
+    /// tables in the default schema. They take precedence over the type overrides, and are useful for
+    /// branded types. Only Postgres and SQLite tell which table column a result column originates from.
+    #[serde(default)]
+    pub columns: HashMap<String, TypeOverride>,
+    /// Postgres types that node-postgres is given a parser for with `types.setTypeParser`, keyed by name or
+    /// OID like the type overrides. Composite types are then typed as objects of their fields, and arrays of
+    /// enums, composite types and domains as arrays, instead of their text representation.
+    #[serde(default)]
+    pub parsed_types: Vec<String>,
+    /// Also generate the values of Postgres enums, in a `squeeel.<lib>.enums.ts` file next to the types, so
+    /// they can be used at runtime
+    pub enum_values: Option<EnumValues>,
+    /// Whether the better-sqlite3 database is configured with `defaultSafeIntegers()`, so statements return
+    /// integers as `bigint` unless they opt out with `safeIntegers(false)`
+    #[serde(default)]
+    pub default_safe_integers: bool,
+    /// Type nullable columns as optional properties, `T | undefined`, instead of `T | null`. This is only
+    /// useful if the driver is wrapped to leave out `null` values.
+    #[serde(default)]
+    pub null_as_undefined: bool,
+    /// The options the mysql2 connection is created with, which decide how values are decoded. They are
+    /// detected from the options passed to `createConnection` or `createPool`, and the ones set here take
+    /// precedence. Also used by mysql, which shares the options except `decimalNumbers`, and by knex and the
+    /// ORMs on MySQL.
+    #[serde(default)]
+    pub mysql2: MySql2Options,
+    /// Fail on problems with the queries that are otherwise reported as warnings, like result columns with
+    /// the same name
+    #[serde(default)]
+    pub strict: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnumValues {
+    /// `export const OrderStatus = { Pending: "pending" } as const`
+    Const,
+    /// `export enum OrderStatus { Pending = "pending" }`
+    Enum,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct MySql2Options {
+    /// `BIGINT` values that don't fit in a number are returned as strings
+    pub support_big_numbers: Option<bool>,
+    /// With `supportBigNumbers`, `BIGINT` values are always returned as strings
+    pub big_number_strings: Option<bool>,
+    /// `DATE`, `DATETIME` and `TIMESTAMP` values, or only the listed types, are returned as strings
+    pub date_strings: Option<DateStrings>,
+    /// `DECIMAL` values are returned as numbers instead of strings
+    pub decimal_numbers: Option<bool>,
+    /// Several statements can be run in one query
+    pub multiple_statements: Option<bool>,
+    /// `TINYINT(1)` values are cast to booleans by a `typeCast` function, which can't be detected
+    pub tiny_int_as_boolean: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum DateStrings {
+    All(bool),
+    Types(Vec<String>),
+}
+
+impl MySql2Options {
+    /// The options that are set, and the other options where they are not
+    pub fn or(self, other: MySql2Options) -> Self {
+        Self {
+            support_big_numbers: self.support_big_numbers.or(other.support_big_numbers),
+            big_number_strings: self.big_number_strings.or(other.big_number_strings),
+            date_strings: self.date_strings.or(other.date_strings),
+            decimal_numbers: self.decimal_numbers.or(other.decimal_numbers),
+            multiple_statements: self.multiple_statements.or(other.multiple_statements),
+            tiny_int_as_boolean: self.tiny_int_as_boolean.or(other.tiny_int_as_boolean),
+        }
+    }
+
+    /// Whether values of a date type, like `DATETIME`, are returned as strings
+    pub fn date_string(&self, ty: &str) -> bool {
+        match &self.date_strings {
+            None => false,
+            Some(DateStrings::All(all)) => *all,
+            Some(DateStrings::Types(types)) => types.iter().any(|t| t.eq_ignore_ascii_case(ty)),
+        }
+    }
+}
+
+/// A TS type expression, either on its own or with the import it needs
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum TypeOverride {
+    Type(String),
+    WithImport {
+        #[serde(rename = "type")]
+        ty: String,
+        import: Option<TypeImport>,
+    },
+}
+
+/// A type only import, like `import type { Decimal } from "decimal.js"`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TypeImport {
+    pub name: String,
+    pub from: String,
+}
+
+impl TypeOverride {
+    pub fn ts_type(&self) -> TsType {
+        let ty = match self {
+            TypeOverride::Type(ty) | TypeOverride::WithImport { ty, .. } => ty,
+        };
+        // The types are validated when the config is loaded
+        parse_ts_type(ty).unwrap()
+    }
+
+    pub fn import(&self) -> Option<&TypeImport> {
+        match self {
+            TypeOverride::Type(_) => None,
+            TypeOverride::WithImport { import, .. } => import.as_ref(),
+        }
+    }
+}
+
+impl Config {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let config: Config = serde_json::from_str(&content).map_err(|err| {
+            anyhow::anyhow!("Invalid config in {}: {err}", path.to_string_lossy())
+        })?;
+        for (key, type_override) in config.types.iter().chain(&config.columns) {
+            let (TypeOverride::Type(ty) | TypeOverride::WithImport { ty, .. }) = type_override;
+            if parse_ts_type(ty).is_none() {
+                return Err(anyhow::anyhow!(
+                    "Invalid config in {}: `{ty}` is not a valid TS type for `{key}`",
+                    path.to_string_lossy()
+                ));
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// The override for a database type, looked up by its OID first, and then by its name, ignoring case
+    pub fn type_override(&self, name: &str, oid: Option<u32>) -> Option<&TypeOverride> {
+        oid.and_then(|oid| self.types.get(&oid.to_string()))
+            .or_else(|| {
+                self.types
+                    .iter()
+                    .find(|(db_type, _)| db_type.eq_ignore_ascii_case(name))
+                    .map(|(_, type_override)| type_override)
+            })
+    }
+
+    /// The override for a table column
+    pub fn column_override(&self, origin: &ColumnOrigin) -> Option<&TypeOverride> {
+        let ColumnOrigin {
+            schema,
+            table,
+            column,
+        } = origin;
+        self.columns
+            .get(&format!("{schema}.{table}.{column}"))
+            .or_else(|| {
+                // The default schemas of Postgres and SQLite
+                (schema == "public" || schema == "main")
+                    .then(|| self.columns.get(&format!("{table}.{column}")))
+                    .flatten()
+            })
+    }
+
+    /// The imports needed by the type and column overrides
+    pub fn type_imports(&self) -> Vec<&TypeImport> {
+        let mut imports: Vec<&TypeImport> = self
+            .types
+            .values()
+            .chain(self.columns.values())
+            .filter_map(TypeOverride::import)
+            .collect();
+        imports.sort_by(|a, b| (&a.from, &a.name).cmp(&(&b.from, &b.name)));
+        imports.dedup();
+        imports
+    }
+}
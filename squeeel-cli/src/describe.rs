@@ -1,13 +1,102 @@
-use sqlx::{Executor, MySql, MySqlPool, Postgres, Sqlite};
+use crate::Dialect;
+use crate::utils::result_columns::result_column_origins;
+use libsqlite3_sys::{
+    SQLITE_OK, sqlite3, sqlite3_column_count, sqlite3_column_database_name,
+    sqlite3_column_decltype, sqlite3_column_origin_name, sqlite3_column_table_name,
+    sqlite3_finalize, sqlite3_prepare_v2,
+};
+use sqlx::{Column, Executor, MySql, MySqlPool, Postgres, Sqlite, TypeInfo};
 use sqlx::{PgPool, SqlitePool};
 use sqlx_core::describe::Describe;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString, c_char};
+use std::ptr::NonNull;
 use tokio::sync::OnceCell;
 
 pub trait DbExt: sqlx::Database {
     type Db: sqlx::Database;
 
+    const DIALECT: Dialect;
+
     async fn describe(query: String) -> Result<Describe<Self::Db>, sqlx::Error>;
     async fn get_table_names() -> Result<Vec<String>, sqlx::Error>;
+
+    /// The named enum types of the database, for databases that have them
+    async fn get_enums() -> Result<Vec<EnumType>, sqlx::Error> {
+        Ok(Vec::new())
+    }
+
+    /// The OID of a type, for databases that identify their types by one
+    fn type_oid(_ty: &<Self::Db as sqlx::Database>::TypeInfo) -> Option<u32> {
+        None
+    }
+
+    /// What the database tells about each result column beyond its type, like the table column it originates
+    /// from
+    async fn column_info(
+        _query: &str,
+        describe: &Describe<Self::Db>,
+    ) -> Result<Vec<ColumnInfo>, sqlx::Error> {
+        Ok(vec![ColumnInfo::default(); describe.columns.len()])
+    }
+
+    /// How the database fills in each column of a table when a row is inserted, in the order of the columns
+    /// of `describe`, the description of `SELECT * FROM {table}`
+    async fn table_columns(
+        table: &str,
+        describe: &Describe<Self::Db>,
+    ) -> Result<Vec<TableColumn>, sqlx::Error>;
+}
+
+/// An enum type, like `public.order_status`
+#[derive(Debug, Clone)]
+pub struct EnumType {
+    pub schema: String,
+    pub name: String,
+    pub variants: Vec<String>,
+}
+
+/// A column of a table, like `public.users.id`
+#[derive(Debug, Clone)]
+pub struct ColumnOrigin {
+    pub schema: String,
+    pub table: String,
+    pub column: String,
+}
+
+/// What the database tells about a result column, beyond its type
+#[derive(Debug, Clone, Default)]
+pub struct ColumnInfo {
+    /// The table column it originates from, for databases that expose it. Columns that are the result of an
+    /// expression have no origin.
+    pub origin: Option<ColumnOrigin>,
+    /// The type the column is declared with, for databases where it tells more than the described type,
+    /// like SQLite, where only the declared type decides how values are stored, and MySQL, where it lists the
+    /// values of `ENUM` and `SET` columns
+    pub declared_type: Option<String>,
+    /// Whether the column is in a STRICT SQLite table, where the declared type is enforced
+    pub strict: bool,
+}
+
+/// A column of a table, as the catalog of the database declares it
+#[derive(Debug, Clone, Default)]
+pub struct TableColumn {
+    /// Whether the column has no `NOT NULL` constraint
+    pub nullable: bool,
+    /// Whether the database fills in the column if an insert leaves it out, like columns with a default,
+    /// serial and identity columns, and the `INTEGER PRIMARY KEY` of a SQLite table
+    pub defaulted: bool,
+    /// Whether the database computes the column, so it can't be inserted or updated, like generated columns
+    /// and `GENERATED ALWAYS` identity columns
+    pub generated: bool,
+}
+
+/// A table column from what `describe` tells about its column `i`, for columns the catalog doesn't tell about
+fn described_table_column<Db: sqlx::Database>(describe: &Describe<Db>, i: usize) -> TableColumn {
+    TableColumn {
+        nullable: describe.nullable(i).unwrap_or(true),
+        ..Default::default()
+    }
 }
 
 static PG_POOL: OnceCell<PgPool> = OnceCell::const_new();
@@ -31,6 +120,8 @@ pub async fn init_my_sql_pool(database_url: &str) -> anyhow::Result<()> {
 impl DbExt for Postgres {
     type Db = Postgres;
 
+    const DIALECT: Dialect = Dialect::Postgres;
+
     async fn describe(query: String) -> Result<Describe<Self::Db>, sqlx::Error> {
         PG_POOL.get().unwrap().describe(&query).await
     }
@@ -41,11 +132,111 @@ impl DbExt for Postgres {
 
         Ok(table_names)
     }
+
+    async fn get_enums() -> Result<Vec<EnumType>, sqlx::Error> {
+        let pool = PG_POOL.get().unwrap();
+        let enums: Vec<(String, String, Vec<String>)> = sqlx::query_as("SELECT n.nspname::text, t.typname::text, array_agg(e.enumlabel::text ORDER BY e.enumsortorder) FROM pg_catalog.pg_type t JOIN pg_catalog.pg_enum e ON e.enumtypid = t.oid JOIN pg_catalog.pg_namespace n ON n.oid = t.typnamespace GROUP BY n.nspname, t.typname ORDER BY n.nspname, t.typname;")
+            .fetch_all(pool)
+            .await?;
+
+        Ok(enums
+            .into_iter()
+            .map(|(schema, name, variants)| EnumType {
+                schema,
+                name,
+                variants,
+            })
+            .collect())
+    }
+
+    fn type_oid(ty: &<Self::Db as sqlx::Database>::TypeInfo) -> Option<u32> {
+        ty.oid().map(|oid| oid.0)
+    }
+
+    async fn column_info(
+        _query: &str,
+        describe: &Describe<Self::Db>,
+    ) -> Result<Vec<ColumnInfo>, sqlx::Error> {
+        let pool = PG_POOL.get().unwrap();
+        let mut column_info = Vec::with_capacity(describe.columns.len());
+        for column in &describe.columns {
+            let (Some(relation_id), Some(attribute_no)) =
+                (column.relation_id(), column.relation_attribute_no())
+            else {
+                column_info.push(ColumnInfo::default());
+                continue;
+            };
+            let origin: Option<(String, String, String)> = sqlx::query_as(
+                "SELECT n.nspname::text, c.relname::text, a.attname::text FROM pg_catalog.pg_attribute a JOIN pg_catalog.pg_class c ON c.oid = a.attrelid JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace WHERE a.attrelid = $1 AND a.attnum = $2;",
+            )
+            .bind(relation_id)
+            .bind(attribute_no)
+            .fetch_optional(pool)
+            .await?;
+            column_info.push(ColumnInfo {
+                origin: origin.map(|(schema, table, column)| ColumnOrigin {
+                    schema,
+                    table,
+                    column,
+                }),
+                ..Default::default()
+            });
+        }
+
+        Ok(column_info)
+    }
+
+    async fn table_columns(
+        _table: &str,
+        describe: &Describe<Self::Db>,
+    ) -> Result<Vec<TableColumn>, sqlx::Error> {
+        let pool = PG_POOL.get().unwrap();
+        let Some(relation_id) = describe
+            .columns
+            .iter()
+            .find_map(|column| column.relation_id())
+        else {
+            return Ok((0..describe.columns.len())
+                .map(|i| described_table_column(describe, i))
+                .collect());
+        };
+        // `attidentity` is `a` for `GENERATED ALWAYS` and `d` for `GENERATED BY DEFAULT` identity columns, and
+        // `attgenerated` is `s` for generated columns, which also have a default expression
+        let attributes: Vec<(i16, bool, bool, String, String)> = sqlx::query_as(
+            "SELECT attnum, attnotnull, atthasdef, attidentity::text, attgenerated::text FROM pg_catalog.pg_attribute WHERE attrelid = $1 AND attnum > 0 AND NOT attisdropped;",
+        )
+        .bind(relation_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(describe
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let attribute = column.relation_attribute_no().and_then(|attribute_no| {
+                    attributes
+                        .iter()
+                        .find(|(attnum, ..)| *attnum == attribute_no)
+                });
+                match attribute {
+                    Some((_, not_null, has_default, identity, generated)) => TableColumn {
+                        nullable: !not_null,
+                        defaulted: *has_default || !identity.is_empty(),
+                        generated: identity == "a" || !generated.is_empty(),
+                    },
+                    None => described_table_column(describe, i),
+                }
+            })
+            .collect())
+    }
 }
 
 impl DbExt for Sqlite {
     type Db = Sqlite;
 
+    const DIALECT: Dialect = Dialect::Sqlite;
+
     async fn describe(query: String) -> Result<Describe<Self::Db>, sqlx::Error> {
         SQLITE_POOL.get().unwrap().describe(&query).await
     }
@@ -60,11 +251,140 @@ impl DbExt for Sqlite {
 
         Ok(table_names)
     }
+
+    async fn column_info(
+        query: &str,
+        describe: &Describe<Self::Db>,
+    ) -> Result<Vec<ColumnInfo>, sqlx::Error> {
+        let mut conn = SQLITE_POOL.get().unwrap().acquire().await?;
+        let columns = {
+            let mut handle = conn.lock_handle().await?;
+            sqlite_column_metadata(handle.as_raw_handle(), query)
+        };
+        let Some(mut columns) = columns else {
+            return Ok(vec![ColumnInfo::default(); describe.columns.len()]);
+        };
+
+        for column in &mut columns {
+            let Some(origin) = &column.origin else {
+                continue;
+            };
+            // `strict` was added to `pragma_table_list` in SQLite 3.37, older versions have no STRICT tables
+            column.strict = sqlx::query_scalar(
+                "SELECT strict FROM pragma_table_list WHERE schema = ? AND name = ?;",
+            )
+            .bind(&origin.schema)
+            .bind(&origin.table)
+            .fetch_optional(&mut *conn)
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(false);
+        }
+
+        Ok(columns)
+    }
+
+    async fn table_columns(
+        table: &str,
+        describe: &Describe<Self::Db>,
+    ) -> Result<Vec<TableColumn>, sqlx::Error> {
+        let pool = SQLITE_POOL.get().unwrap();
+        // `hidden` is 2 and 3 for virtual and stored generated columns
+        let columns: Vec<(String, String, bool, bool, i64, i64)> = sqlx::query_as(
+            "SELECT name, type, \"notnull\", dflt_value IS NOT NULL, pk, hidden FROM pragma_table_xinfo(?);",
+        )
+        .bind(table)
+        .fetch_all(pool)
+        .await?;
+        // A single `INTEGER PRIMARY KEY` column is an alias of the rowid, which SQLite assigns if it is left out
+        let rowid_alias = match &columns
+            .iter()
+            .filter(|(_, _, _, _, pk, _)| *pk > 0)
+            .collect::<Vec<_>>()[..]
+        {
+            [(name, ty, ..)] if ty.eq_ignore_ascii_case("INTEGER") => Some(name),
+            _ => None,
+        };
+
+        Ok(describe
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let name = column.name();
+                match columns
+                    .iter()
+                    .find(|(column, ..)| column.eq_ignore_ascii_case(name))
+                {
+                    Some((column, _, not_null, has_default, _, hidden)) => TableColumn {
+                        // The rowid can't be null, even if it isn't declared `NOT NULL`
+                        nullable: !not_null && rowid_alias != Some(column),
+                        defaulted: *has_default || rowid_alias == Some(column),
+                        generated: matches!(hidden, 2 | 3),
+                    },
+                    None => described_table_column(describe, i),
+                }
+            })
+            .collect())
+    }
+}
+
+/// The table column and declared type of each result column of a query, as SQLite reports them. sqlx only
+/// uses these to decide the type of a column, so we prepare the query ourselves to get them.
+fn sqlite_column_metadata(db: NonNull<sqlite3>, query: &str) -> Option<Vec<ColumnInfo>> {
+    let query = CString::new(query).ok()?;
+    let mut stmt = std::ptr::null_mut();
+    // SAFETY: the connection is locked while the handle is used, and the statement is finalized before
+    // returning. The strings returned by SQLite are copied before the statement is finalized.
+    unsafe {
+        let status = sqlite3_prepare_v2(
+            db.as_ptr(),
+            query.as_ptr(),
+            -1,
+            &mut stmt,
+            std::ptr::null_mut(),
+        );
+        if status != SQLITE_OK || stmt.is_null() {
+            sqlite3_finalize(stmt);
+            return None;
+        }
+
+        let text = |ptr: *const c_char| {
+            (!ptr.is_null()).then(|| CStr::from_ptr(ptr).to_string_lossy().into_owned())
+        };
+        let columns = (0..sqlite3_column_count(stmt))
+            .map(|i| {
+                let origin = match (
+                    text(sqlite3_column_database_name(stmt, i)),
+                    text(sqlite3_column_table_name(stmt, i)),
+                    text(sqlite3_column_origin_name(stmt, i)),
+                ) {
+                    (Some(schema), Some(table), Some(column)) => Some(ColumnOrigin {
+                        schema,
+                        table,
+                        column,
+                    }),
+                    _ => None,
+                };
+                ColumnInfo {
+                    origin,
+                    declared_type: text(sqlite3_column_decltype(stmt, i)),
+                    strict: false,
+                }
+            })
+            .collect();
+        sqlite3_finalize(stmt);
+
+        Some(columns)
+    }
 }
 
 impl DbExt for MySql {
     type Db = MySql;
 
+    const DIALECT: Dialect = Dialect::MySql;
+
     async fn describe(query: String) -> Result<Describe<Self::Db>, sqlx::Error> {
         MY_SQL_POOL.get().unwrap().describe(&query).await
     }
@@ -78,4 +398,121 @@ impl DbExt for MySql {
 
         Ok(table_names)
     }
+
+    async fn column_info(
+        query: &str,
+        describe: &Describe<Self::Db>,
+    ) -> Result<Vec<ColumnInfo>, sqlx::Error> {
+        let mut column_info = vec![ColumnInfo::default(); describe.columns.len()];
+        // Only the declared types of `ENUM`, `SET` and spatial columns tell more than the described type.
+        // Servers that send `SET` values as strings with a flag have them described as `CHAR`.
+        let has_declared_types = describe.columns.iter().any(|column| {
+            matches!(
+                column.type_info().name(),
+                "ENUM" | "SET" | "CHAR" | "GEOMETRY"
+            )
+        });
+        if !has_declared_types {
+            return Ok(column_info);
+        }
+
+        let declared_types = mysql_declared_types().await?;
+        let columns = declared_types
+            .iter()
+            .map(|(table, columns)| {
+                let names = columns.iter().map(|(column, _)| column.clone()).collect();
+                (table.clone(), names)
+            })
+            .collect();
+        let Some(origins) = result_column_origins(query, Dialect::MySql, &columns)
+            .filter(|origins| origins.len() == column_info.len())
+        else {
+            return Ok(column_info);
+        };
+        for (info, origin) in column_info.iter_mut().zip(origins) {
+            info.declared_type = origin.and_then(|(table, column)| {
+                declared_types[&table]
+                    .iter()
+                    .find(|(name, _)| *name == column)
+                    .map(|(_, declared_type)| declared_type.clone())
+            });
+        }
+
+        Ok(column_info)
+    }
+
+    async fn table_columns(
+        table: &str,
+        describe: &Describe<Self::Db>,
+    ) -> Result<Vec<TableColumn>, sqlx::Error> {
+        let pool = MY_SQL_POOL.get().unwrap();
+        // `extra` tells `auto_increment` and `VIRTUAL GENERATED` or `STORED GENERATED` columns. The strings are
+        // binary in some versions of MySQL.
+        let columns: Vec<(Vec<u8>, i64, i64, Vec<u8>)> = sqlx::query_as(
+            "SELECT column_name, is_nullable = 'YES', column_default IS NOT NULL, extra FROM information_schema.columns WHERE table_schema = DATABASE() AND table_name = ?;",
+        )
+        .bind(table)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(describe
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let name = column.name();
+                let column = columns.iter().find(|(column, ..)| {
+                    String::from_utf8_lossy(column).eq_ignore_ascii_case(name)
+                });
+                match column {
+                    Some((_, nullable, has_default, extra)) => {
+                        let extra = String::from_utf8_lossy(extra).to_lowercase();
+                        TableColumn {
+                            nullable: *nullable != 0,
+                            defaulted: *has_default != 0 || extra.contains("auto_increment"),
+                            generated: extra.contains("generated")
+                                && !extra.contains("default_generated"),
+                        }
+                    }
+                    None => described_table_column(describe, i),
+                }
+            })
+            .collect())
+    }
+}
+
+static MY_SQL_DECLARED_TYPES: OnceCell<HashMap<String, Vec<(String, String)>>> =
+    OnceCell::const_new();
+
+/// The declared type of each column of the tables of the database, like `enum('a','b')`, keyed by the
+/// lowercased table name, with the columns in order. They are read once per run.
+async fn mysql_declared_types()
+-> Result<&'static HashMap<String, Vec<(String, String)>>, sqlx::Error> {
+    MY_SQL_DECLARED_TYPES
+        .get_or_try_init(fetch_mysql_declared_types)
+        .await
+}
+
+async fn fetch_mysql_declared_types() -> Result<HashMap<String, Vec<(String, String)>>, sqlx::Error>
+{
+    let pool = MY_SQL_POOL.get().unwrap();
+    // The strings are binary in some versions of MySQL
+    let columns: Vec<(Vec<u8>, Vec<u8>, Vec<u8>)> = sqlx::query_as(
+        "SELECT table_name, column_name, column_type FROM information_schema.columns WHERE table_schema = DATABASE() ORDER BY table_name, ordinal_position;",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut declared_types: HashMap<String, Vec<(String, String)>> = HashMap::new();
+    for (table, column, declared_type) in columns {
+        declared_types
+            .entry(String::from_utf8_lossy(&table).to_lowercase())
+            .or_default()
+            .push((
+                String::from_utf8_lossy(&column).into_owned(),
+                String::from_utf8_lossy(&declared_type).into_owned(),
+            ));
+    }
+
+    Ok(declared_types)
 }
@@ -1,7 +1,10 @@
+mod config;
+pub use config::{CONFIG_FILE_NAME, Config};
 mod sql_libs;
 pub use sql_libs::*;
 mod visitor;
-pub use visitor::{Query, visit_ast};
+pub use visitor::{Query, SOURCE_START, visit_ast};
 mod describe;
 pub use describe::{init_my_sql_pool, init_pg_pool, init_sqlite_pool};
 mod utils;
+pub use utils::ast::imports_package;
@@ -2,9 +2,18 @@ use anyhow::anyhow;
 use clap::Parser as ClapParser;
 use clap::Subcommand;
 use serde::Deserialize;
+use squeeel_cli::CONFIG_FILE_NAME;
+use squeeel_cli::Config;
 use squeeel_cli::Dialect;
 use squeeel_cli::Query;
+use squeeel_cli::SOURCE_START;
 use squeeel_cli::SupportedLib;
+use squeeel_cli::detect_knex_client;
+use squeeel_cli::detect_mikro_orm_type;
+use squeeel_cli::detect_mysql2_options;
+use squeeel_cli::detect_sequelize_dialect;
+use squeeel_cli::detect_typeorm_type;
+use squeeel_cli::imports_package;
 use squeeel_cli::init_my_sql_pool;
 use squeeel_cli::init_pg_pool;
 use squeeel_cli::init_sqlite_pool;
@@ -14,8 +23,10 @@ use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use swc_common::BytePos;
 use swc_common::SourceMap;
 use swc_common::sync::Lrc;
+use swc_ecma_ast::Module;
 use swc_ecma_codegen::{Emitter, text_writer::JsWriter};
 use swc_ecma_parser::TsSyntax;
 use swc_ecma_parser::{Parser, StringInput, Syntax, lexer::Lexer};
@@ -39,6 +50,10 @@ pub struct GenCommandOptions {
     #[clap(default_value = ".")]
     pub project_root: PathBuf,
 
+    /// The path to the config file. Defaults to squeeel.config.json in the project root, if it exists.
+    #[arg(long)]
+    config: Option<PathBuf>,
+
     /// Set the database url. This default to the DATABASE_URL env var
     #[arg(long)]
     database_url: Option<String>,
@@ -51,6 +66,11 @@ pub struct GenCommandOptions {
     /// Set the database url specifically for mysql libs. This falls back to the --database-url
     #[arg(long)]
     my_sql_database_url: Option<String>,
+
+    /// Fail on problems with the queries that are otherwise reported as warnings. This can also be set in the
+    /// config file.
+    #[arg(long)]
+    strict: bool,
 }
 
 fn find_package_json_dir(from_dir: &Path) -> anyhow::Result<&Path> {
@@ -79,19 +99,152 @@ struct PackageJson {
     dependencies: HashMap<String, serde_json::Value>,
 }
 
-fn detect_sql_libs_in_package_json(package_json_path: &Path) -> anyhow::Result<Vec<SupportedLib>> {
+fn detect_sql_libs_in_package_json(
+    package_json_path: &Path,
+    sources: &[(PathBuf, Module)],
+    config: &Config,
+) -> anyhow::Result<Vec<SupportedLib>> {
     let content = std::fs::read_to_string(package_json_path)?;
 
     let package_json: PackageJson = serde_json::from_str(&content)?;
-    let libs: Vec<_> = package_json
-        .dependencies
-        .into_keys()
-        .filter_map(|lib| SupportedLib::try_from(lib).ok())
-        .collect();
+    let root_dir = package_json_path.parent().unwrap_or(Path::new("."));
+    let dependencies = &package_json.dependencies;
+    let mut libs = Vec::new();
+    for lib in dependencies.keys() {
+        match lib.as_str() {
+            "knex" => libs.push(SupportedLib::Knex(detect_dialect(
+                "knex",
+                config,
+                detect_knex_configured_client(root_dir, sources),
+                dependencies,
+            )?)),
+            "typeorm" => libs.push(SupportedLib::TypeOrm(detect_dialect(
+                "typeorm",
+                config,
+                detect_typeorm_configured_type(root_dir, sources),
+                dependencies,
+            )?)),
+            "@mikro-orm/core" => libs.push(SupportedLib::MikroOrm(detect_dialect(
+                "mikro-orm",
+                config,
+                sources
+                    .iter()
+                    .find_map(|(_, module)| detect_mikro_orm_type(module)),
+                dependencies,
+            )?)),
+            "sequelize" => libs.push(SupportedLib::Sequelize(detect_dialect(
+                "sequelize",
+                config,
+                sources
+                    .iter()
+                    .find_map(|(_, module)| detect_sequelize_dialect(module)),
+                dependencies,
+            )?)),
+            _ => {
+                if let Ok(lib) = SupportedLib::try_from(lib.clone()) {
+                    libs.push(lib);
+                }
+            }
+        }
+    }
+
+    // mysql and mysql2 have the same `connection.query` API, so their queries can't be told apart. Only the
+    // one the sources import is used.
+    if libs.contains(&SupportedLib::MySql) && libs.contains(&SupportedLib::MySql2) {
+        let imported = |package| {
+            sources
+                .iter()
+                .any(|(_, module)| imports_package(module, package))
+        };
+        match (imported("mysql"), imported("mysql2")) {
+            (true, false) => libs.retain(|lib| *lib != SupportedLib::MySql2),
+            (false, true) => libs.retain(|lib| *lib != SupportedLib::MySql),
+            _ => {
+                return Err(anyhow!(
+                    "Both mysql and mysql2 are dependencies, and their queries can't be told apart. Please use only one of them in your sources"
+                ));
+            }
+        }
+    }
 
     Ok(libs)
 }
 
+/// Libs like knex and ORMs can be used with any database. The dialect is taken from the config file, then
+/// from how the lib itself is configured, and lastly from the driver package that is installed.
+fn detect_dialect(
+    lib: &str,
+    config: &Config,
+    configured_client: Option<String>,
+    dependencies: &HashMap<String, serde_json::Value>,
+) -> anyhow::Result<Dialect> {
+    if let Some(dialect) = config.dialect {
+        return Ok(dialect);
+    }
+
+    if let Some(client) = configured_client {
+        return Dialect::from_client_name(&client).ok_or_else(|| {
+            anyhow!(
+                "{lib} is configured to use `{client}`, which is not supported. Please set the `dialect` in {CONFIG_FILE_NAME} if it is compatible with one of `postgres`, `mysql` or `sqlite`"
+            )
+        });
+    }
+
+    let drivers: HashSet<_> = dependencies
+        .keys()
+        .filter_map(|dependency| Dialect::from_driver_package(dependency))
+        .collect();
+    if drivers.len() == 1 {
+        return Ok(drivers.into_iter().next().unwrap());
+    }
+
+    Err(anyhow!(
+        "Could not detect which database {lib} is used with. Please set the `dialect` in {CONFIG_FILE_NAME}"
+    ))
+}
+
+/// The `client` in a knexfile, or in a `knex({ client })` call
+fn detect_knex_configured_client(root_dir: &Path, sources: &[(PathBuf, Module)]) -> Option<String> {
+    ["knexfile.js", "knexfile.cjs", "knexfile.mjs", "knexfile.ts"]
+        .iter()
+        .map(|name| root_dir.join(name))
+        .filter(|path| path.exists())
+        .find_map(|path| detect_knex_client(&parse_module(&path).ok()?, true))
+        .or_else(|| {
+            sources
+                .iter()
+                .find_map(|(_, module)| detect_knex_client(module, false))
+        })
+}
+
+/// The `type` in a `new DataSource({ type })` call, or in an ormconfig file
+fn detect_typeorm_configured_type(
+    root_dir: &Path,
+    sources: &[(PathBuf, Module)],
+) -> Option<String> {
+    sources
+        .iter()
+        .find_map(|(_, module)| detect_typeorm_type(module, false))
+        .or_else(|| {
+            let content = std::fs::read_to_string(root_dir.join("ormconfig.json")).ok()?;
+            let ormconfig: serde_json::Value = serde_json::from_str(&content).ok()?;
+            // The ormconfig is either a single connection, or a list of them
+            let connection = ormconfig
+                .as_array()
+                .and_then(|connections| connections.first());
+            connection.unwrap_or(&ormconfig)["type"]
+                .as_str()
+                .map(str::to_string)
+        })
+        .or_else(|| {
+            ["ormconfig.js", "ormconfig.ts"]
+                .iter()
+                .map(|name| root_dir.join(name))
+                .filter(|path| path.exists())
+                .find_map(|path| detect_typeorm_type(&parse_module(&path).ok()?, true))
+        })
+}
+
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
     match cli.command {
@@ -105,12 +258,29 @@ fn gen_command(options: GenCommandOptions) -> anyhow::Result<()> {
     println!("Generating result and parameter types for sql queries\n");
     let root_dir = find_package_json_dir(&options.project_root)?;
     // println!(" - Found package root located at {root_dir:?}");
-    let sql_libs = detect_sql_libs_in_package_json(&root_dir.join("package.json"))?;
+    let mut config = match &options.config {
+        Some(config_path) => Config::load(config_path)?,
+        None if root_dir.join(CONFIG_FILE_NAME).exists() => {
+            Config::load(&root_dir.join(CONFIG_FILE_NAME))?
+        }
+        None => Config::default(),
+    };
+    config.strict |= options.strict;
+    let sources = parse_source_files(root_dir);
+    let sql_libs =
+        detect_sql_libs_in_package_json(&root_dir.join("package.json"), &sources, &config)?;
     if sql_libs.is_empty() {
         return Err(anyhow::anyhow!(
             "Did not detect any supported libraries. See https://github.com/SorenHolstHansen/squeeel#supported-libraries for supported libs"
         ));
     }
+    if sql_libs.iter().any(|lib| lib.dialect() == Dialect::MySql)
+        && let Some(detected) = sources
+            .iter()
+            .find_map(|(_, module)| detect_mysql2_options(module))
+    {
+        config.mysql2 = config.mysql2.or(detected);
+    }
     println!(
         " - Detected the following libraries: {}",
         sql_libs
@@ -120,10 +290,10 @@ fn gen_command(options: GenCommandOptions) -> anyhow::Result<()> {
             .join(", ")
     );
 
-    let queries = detect_queries(root_dir, sql_libs);
-    let queries_by_lib: HashMap<SupportedLib, Vec<String>> =
+    let queries = detect_queries(&sources, &sql_libs);
+    let queries_by_lib: HashMap<SupportedLib, Vec<Query>> =
         queries.into_iter().fold(HashMap::new(), |mut acc, query| {
-            acc.entry(query.lib).or_default().push(query.query);
+            acc.entry(query.lib).or_default().push(query);
             acc
         });
     println!(
@@ -141,7 +311,7 @@ fn gen_command(options: GenCommandOptions) -> anyhow::Result<()> {
         .unwrap()
         .block_on(async {
             init_databases(root_dir, queries_by_lib.keys(), &options).await?;
-            create_d_ts_files(root_dir, queries_by_lib).await
+            create_d_ts_files(root_dir, queries_by_lib, config).await
         })?;
 
     println!("\nDone!");
@@ -149,8 +319,40 @@ fn gen_command(options: GenCommandOptions) -> anyhow::Result<()> {
     Ok(())
 }
 
-fn detect_queries(dir: &Path, supported_libs: Vec<SupportedLib>) -> Vec<Query> {
-    let supported_libs = Arc::new(supported_libs);
+fn read_source(path: &Path) -> anyhow::Result<String> {
+    let source = std::fs::read_to_string(path)?;
+    Ok(match source.strip_prefix('\u{feff}') {
+        Some(source) => source.to_string(),
+        None => source,
+    })
+}
+
+fn parse_module(path: &Path) -> anyhow::Result<Module> {
+    let source = read_source(path)?;
+
+    let lexer = Lexer::new(
+        Syntax::Typescript(TsSyntax {
+            no_early_errors: true,
+            tsx: path.to_string_lossy().ends_with(".tsx"),
+            ..Default::default()
+        }),
+        Default::default(),
+        StringInput::new(
+            &source,
+            SOURCE_START,
+            SOURCE_START + BytePos(source.len() as u32),
+        ),
+        None,
+    );
+
+    let mut parser = Parser::new_from(lexer);
+
+    parser
+        .parse_typescript_module()
+        .map_err(|err| anyhow!("Could not parse {}: {:?}", path.to_string_lossy(), err))
+}
+
+fn parse_source_files(dir: &Path) -> Vec<(PathBuf, Module)> {
     let mut handles = Vec::new();
     for entry in walkdir::WalkDir::new(dir) {
         let entry = entry.unwrap();
@@ -165,33 +367,26 @@ fn detect_queries(dir: &Path, supported_libs: Vec<SupportedLib>) -> Vec<Query> {
             continue;
         }
 
-        let supported_libs = supported_libs.clone();
         handles.push(std::thread::spawn(move || {
-            let cm: Lrc<SourceMap> = Default::default();
-
-            let fm = cm.load_file(entry.path()).unwrap();
-
-            let lexer = Lexer::new(
-                Syntax::Typescript(TsSyntax {
-                    no_early_errors: true,
-                    tsx: entry.path().to_string_lossy().ends_with(".tsx"),
-                    ..Default::default()
-                }),
-                Default::default(),
-                StringInput::from(&*fm),
-                None,
-            );
-
-            let mut parser = Parser::new_from(lexer);
-
-            let module = parser.parse_typescript_module().unwrap();
-            visit_ast(&supported_libs, &module, entry.path()).unwrap()
+            let module = parse_module(entry.path()).unwrap();
+            (entry.into_path(), module)
         }));
     }
 
     handles
         .into_iter()
-        .flat_map(|handle| handle.join().unwrap())
+        .map(|handle| handle.join().unwrap())
+        .collect()
+}
+
+fn detect_queries(sources: &[(PathBuf, Module)], supported_libs: &[SupportedLib]) -> Vec<Query> {
+    sources
+        .iter()
+        .flat_map(|(path, module)| {
+            // The source is read again to locate the queries in it
+            let source = read_source(path).unwrap();
+            visit_ast(supported_libs, module, path, &source).unwrap()
+        })
         .collect()
 }
 
@@ -295,13 +490,16 @@ Or use one of the following environment variables
 
 async fn create_d_ts_files(
     dir: &Path,
-    queries_by_lib: HashMap<SupportedLib, Vec<String>>,
+    queries_by_lib: HashMap<SupportedLib, Vec<Query>>,
+    config: Config,
 ) -> anyhow::Result<()> {
     // println!(" - Generating .d.ts files");
+    let config = Arc::new(config);
     let mut tasks = Vec::with_capacity(queries_by_lib.keys().len());
     for (lib, queries) in queries_by_lib {
         tasks.push(tokio::spawn({
-            async move { (lib, lib.create_d_ts_file(queries).await) }
+            let config = config.clone();
+            async move { (lib, lib.create_d_ts_file(queries, &config).await) }
         }));
     }
 
@@ -310,28 +508,53 @@ async fn create_d_ts_files(
         outputs.push(task.await.unwrap());
     }
 
-    for (lib, module) in outputs {
-        let cm: Lrc<SourceMap> = Default::default();
-        let code = {
-            let mut buf = Vec::new();
+    let warnings: Vec<&String> = outputs
+        .iter()
+        .flat_map(|(_, (_, _, warnings))| warnings)
+        .collect();
+    if config.strict && !warnings.is_empty() {
+        return Err(anyhow!(
+            "Found the following problems with the queries:\n{}",
+            warnings
+                .iter()
+                .map(|warning| format!(" - {warning}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+    }
+    for warning in warnings {
+        eprintln!("Warning: {warning}");
+    }
 
-            {
-                let mut emitter = Emitter {
-                    cfg: Default::default(),
-                    cm: cm.clone(),
-                    comments: None,
-                    wr: JsWriter::new(cm, "\n", &mut buf, None),
-                };
+    for (lib, (d_ts, enums, _)) in outputs {
+        let d_ts_path = dir.join(format!("src/squeeel.{lib}.d.ts"));
+        std::fs::write(d_ts_path, emit_module(&d_ts)).unwrap();
 
-                emitter.emit_module(&module).unwrap();
-            }
+        let enums_path = dir.join(format!("src/squeeel.{lib}.enums.ts"));
+        if let Some(enums) = enums {
+            std::fs::write(enums_path, emit_module(&enums)).unwrap();
+        } else if enums_path.exists() {
+            std::fs::remove_file(enums_path).unwrap();
+        }
+    }
+
+    Ok(())
+}
 
-            String::from_utf8_lossy(&buf).to_string()
+fn emit_module(module: &Module) -> String {
+    let cm: Lrc<SourceMap> = Default::default();
+    let mut buf = Vec::new();
+
+    {
+        let mut emitter = Emitter {
+            cfg: Default::default(),
+            cm: cm.clone(),
+            comments: None,
+            wr: JsWriter::new(cm, "\n", &mut buf, None),
         };
 
-        let d_ts_path = dir.join(format!("src/squeeel.{lib}.d.ts"));
-        std::fs::write(d_ts_path, code).unwrap();
+        emitter.emit_module(module).unwrap();
     }
 
-    Ok(())
+    String::from_utf8_lossy(&buf).to_string()
 }
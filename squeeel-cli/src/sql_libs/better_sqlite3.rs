@@ -1,17 +1,164 @@
-use crate::sql_libs::SqlLib;
+use crate::config::Config;
+use crate::describe::ColumnInfo;
+use crate::sql_libs::{
+    SqlLib, StatementKind, describe_to_column_types, describe_to_d_ts_query,
+    describe_to_raw_d_ts_type,
+};
+use crate::utils::ast::query_string;
 use crate::utils::ts_types::{
-    TS_NULL_TYPE, TS_NUMBER_TYPE, TS_STRING_TYPE, TS_UNKNOWN_TYPE, ts_type_ref,
+    TS_BIGINT_TYPE, TS_NUMBER_TYPE, TS_STRING_TYPE, TS_UNKNOWN_TYPE, ts_object_type, ts_type_ref,
+    ts_union_type,
 };
+use sqlx_core::describe::Describe;
 use sqlx_core::type_info::TypeInfo;
 use swc_common::BytePos;
-use swc_ecma_ast::{ModuleItem, TsType};
+use swc_ecma_ast::{Expr, ModuleItem, TsType};
 use swc_ecma_parser::{Lexer, Parser, StringInput, Syntax, TsSyntax};
 
-pub struct BetterSqlite3;
+#[derive(Default)]
+pub struct BetterSqlite3 {
+    /// Whether the database is configured with `defaultSafeIntegers()`, so new statements return integers as
+    /// `bigint`
+    default_safe_integers: bool,
+    /// Whether integers are typed as `bigint`, for the rows of statements in `safeIntegers()` mode
+    safe_integers: bool,
+}
+
+impl BetterSqlite3 {
+    pub fn new(default_safe_integers: bool) -> Self {
+        Self {
+            default_safe_integers,
+            safe_integers: false,
+        }
+    }
+}
+
+/// The TS types of numbers read from SQLite, which can be integers or reals. With safe integers, integers
+/// are read as `bigint`.
+fn sqlite_number_types(safe_integers: bool) -> Vec<TsType> {
+    if safe_integers {
+        vec![TS_NUMBER_TYPE, TS_BIGINT_TYPE]
+    } else {
+        vec![TS_NUMBER_TYPE]
+    }
+}
+
+fn sqlite_integer_type(safe_integers: bool) -> TsType {
+    if safe_integers {
+        TS_BIGINT_TYPE
+    } else {
+        TS_NUMBER_TYPE
+    }
+}
+
+/// Any value SQLite can store, given the TS type of blobs
+fn sqlite_value_type(safe_integers: bool, blob_type: TsType) -> TsType {
+    let mut types = sqlite_number_types(safe_integers);
+    types.extend([TS_STRING_TYPE, blob_type]);
+    ts_union_type(types)
+}
+
+/// The values of columns with numeric affinity, which are stored as numbers if they look like one, and as
+/// text otherwise
+fn sqlite_numeric_type(safe_integers: bool) -> TsType {
+    let mut types = sqlite_number_types(safe_integers);
+    types.push(TS_STRING_TYPE);
+    ts_union_type(types)
+}
+
+/// The TS type of a type described by sqlx, given the TS type of blobs. sqlx gives expressions whose type
+/// can't be inferred, including `NULL` literals, the `NULL` type, so they are typed as any value.
+pub(super) fn sqlite_type_to_ts_type(
+    ty: &<sqlx::Sqlite as sqlx::Database>::TypeInfo,
+    safe_integers: bool,
+    blob_type: TsType,
+) -> TsType {
+    match ty.name().to_lowercase().as_str() {
+        "integer" | "boolean" => sqlite_integer_type(safe_integers),
+        "real" => TS_NUMBER_TYPE,
+        "text" | "date" | "time" | "datetime" => TS_STRING_TYPE,
+        "numeric" => sqlite_numeric_type(safe_integers),
+        "blob" => blob_type,
+        "null" => sqlite_value_type(safe_integers, blob_type),
+        _ => TS_UNKNOWN_TYPE,
+    }
+}
+
+/// The TS type of a table column from its declared type, following the affinity rules of SQLite
+/// (https://www.sqlite.org/datatype3.html#determination_of_column_affinity), given the TS type of blobs.
+///
+/// Booleans and dates have numeric affinity, but are stored as `0`/`1` and as text in practice. Only STRICT
+/// tables enforce the declared type, in other tables we assume the values match the affinity of the column.
+pub(super) fn sqlite_declared_type_to_ts_type(
+    declared_type: &str,
+    strict: bool,
+    safe_integers: bool,
+    blob_type: TsType,
+) -> TsType {
+    let declared_type = declared_type.to_uppercase();
+    let name = declared_type.split('(').next().unwrap_or_default().trim();
+    match name {
+        "BOOLEAN" | "BOOL" => sqlite_integer_type(safe_integers),
+        "DATE" | "DATETIME" | "TIMESTAMP" | "TIME" => TS_STRING_TYPE,
+        // Values of ANY columns in STRICT tables, and of columns without a type, are stored as they are
+        "ANY" if strict => sqlite_value_type(safe_integers, blob_type),
+        "" => sqlite_value_type(safe_integers, blob_type),
+        _ if name.contains("INT") => sqlite_integer_type(safe_integers),
+        _ if name.contains("CHAR") || name.contains("CLOB") || name.contains("TEXT") => {
+            TS_STRING_TYPE
+        }
+        _ if name.contains("BLOB") => blob_type,
+        _ if name.contains("REAL") || name.contains("FLOA") || name.contains("DOUB") => {
+            TS_NUMBER_TYPE
+        }
+        _ => sqlite_numeric_type(safe_integers),
+    }
+}
+
+/// The TS type of a parameter, given the type of the column it is bound to with safe integers. Integers can
+/// be bound as numbers or bigints, whatever the mode of the statement.
+fn sqlite_input_type(ts_type: TsType) -> TsType {
+    if ts_type == TS_BIGINT_TYPE {
+        ts_union_type([TS_NUMBER_TYPE, TS_BIGINT_TYPE])
+    } else {
+        ts_type
+    }
+}
+
+/// The row of an expanded statement, where the columns are nested under the table they come from, and
+/// expressions under `$`
+fn describe_to_expanded_d_ts_type(
+    lib: &BetterSqlite3,
+    config: &Config,
+    describe: &Describe<sqlx::Sqlite>,
+    column_info: &[ColumnInfo],
+) -> TsType {
+    let mut tables: Vec<(&str, Vec<_>)> = Vec::new();
+    let columns = describe_to_column_types(lib, config, describe, column_info);
+    for (column, info) in columns.into_iter().zip(column_info) {
+        let table = info
+            .origin
+            .as_ref()
+            .map_or("$", |origin| origin.table.as_str());
+        match tables.iter_mut().find(|(name, _)| *name == table) {
+            Some((_, columns)) => columns.push(column),
+            None => tables.push((table, vec![column])),
+        }
+    }
+
+    ts_object_type(
+        tables
+            .into_iter()
+            .map(|(table, columns)| (table.into(), ts_object_type(columns), false)),
+    )
+}
 
 impl SqlLib for BetterSqlite3 {
     type Db = sqlx::Sqlite;
 
+    // `raw()` and `pluck()` return the rows as arrays, or their first value
+    const RAW_ROWS: bool = true;
+
     fn parse_call_expr(&self, call_expr: &swc_ecma_ast::CallExpr) -> Option<String> {
         let swc_ecma_ast::Callee::Expr(expr) = &call_expr.callee else {
             return None;
@@ -37,32 +184,75 @@ impl SqlLib for BetterSqlite3 {
             return None;
         }
 
-        let query = match &*query_expr.expr {
-            swc_ecma_ast::Expr::Lit(lit) => lit.as_str()?.value.to_string(),
-            swc_ecma_ast::Expr::Tpl(tpl) => tpl
-                .quasis
-                .iter()
-                .map(|quasi| quasi.raw.to_string())
-                .collect::<Vec<_>>()
-                .join(""),
-            _ => return None,
-        };
+        let query = query_string(&query_expr.expr)?;
 
         Some(query)
     }
 
     fn db_type_to_ts_type(&self, ty: &<Self::Db as sqlx::Database>::TypeInfo) -> TsType {
-        match ty.name().to_lowercase().as_str() {
-            "integer" | "real" | "numeric" => TS_NUMBER_TYPE,
-            "text" => TS_STRING_TYPE,
-            "blob" => ts_type_ref("Buffer"),
-            "null" => TS_NULL_TYPE,
-            // "boolean"
-            // "date"
-            // "time"
-            // "datetime"
-            _ => TS_UNKNOWN_TYPE,
-        }
+        sqlite_type_to_ts_type(ty, self.safe_integers, ts_type_ref("Buffer"))
+    }
+
+    fn declared_type_to_ts_type(&self, declared_type: &str, strict: bool) -> Option<TsType> {
+        Some(sqlite_declared_type_to_ts_type(
+            declared_type,
+            strict,
+            self.safe_integers,
+            ts_type_ref("Buffer"),
+        ))
+    }
+
+    fn db_type_to_input_ts_type(&self, ty: &<Self::Db as sqlx::Database>::TypeInfo) -> TsType {
+        sqlite_input_type(sqlite_type_to_ts_type(ty, true, ts_type_ref("Buffer")))
+    }
+
+    fn declared_type_to_input_ts_type(&self, declared_type: &str, strict: bool) -> Option<TsType> {
+        Some(sqlite_input_type(sqlite_declared_type_to_ts_type(
+            declared_type,
+            strict,
+            true,
+            ts_type_ref("Buffer"),
+        )))
+    }
+
+    fn query_type_members(
+        &self,
+        config: &Config,
+        describe: &Describe<sqlx::Sqlite>,
+        column_info: &[ColumnInfo],
+        kind: &StatementKind,
+    ) -> Vec<(Expr, TsType, bool)> {
+        let safe = BetterSqlite3 {
+            safe_integers: true,
+            ..*self
+        };
+        let (safe_return_type, _) =
+            describe_to_d_ts_query(&safe, config, describe, column_info, &[], None);
+        vec![
+            ("reader".into(), kind.reader_ts_type(), false),
+            (
+                "expandReturnType".into(),
+                describe_to_expanded_d_ts_type(self, config, describe, column_info),
+                false,
+            ),
+            (
+                "safeIntegers".into(),
+                ts_object_type([
+                    ("returnType".into(), safe_return_type, false),
+                    (
+                        "rawReturnType".into(),
+                        describe_to_raw_d_ts_type(&safe, config, describe, column_info),
+                        false,
+                    ),
+                    (
+                        "expandReturnType".into(),
+                        describe_to_expanded_d_ts_type(&safe, config, describe, column_info),
+                        false,
+                    ),
+                ]),
+                false,
+            ),
+        ]
     }
 
     fn d_ts_prefix(&self) -> Vec<ModuleItem> {
@@ -75,22 +265,60 @@ impl SqlLib for BetterSqlite3 {
     type ArgumentTypes<F extends VariableArgFunction> = F extends (...args: infer A) => unknown ? A : never;
     type ElementOf<T> = T extends Array<infer E> ? E : T;
 
-    interface Statement<BindParameters extends unknown[], Result = unknown> {
+    type RowTypes = { returnType: unknown; rawReturnType: unknown[]; expandReturnType: unknown };
+    type QueryRowTypes = RowTypes & { reader: boolean; safeIntegers: RowTypes };
+    // The modes are exclusive, turning one on turns the others off
+    type RowMode = "object" | "pluck" | "raw" | "expand";
+    type ModeRow<Types extends RowTypes, Mode extends RowMode> = Mode extends "pluck"
+        ? Types["rawReturnType"][0]
+        : Mode extends "raw"
+          ? Types["rawReturnType"]
+          : Mode extends "expand"
+            ? Types["expandReturnType"]
+            : Types["returnType"];
+    type StatementRow<Types extends QueryRowTypes, Mode extends RowMode, SafeIntegers extends boolean> = ModeRow<
+        SafeIntegers extends true ? Types["safeIntegers"] : Types,
+        Mode
+    >;
+
+    interface Statement<
+        BindParameters extends unknown[],
+        Types extends QueryRowTypes = QueryRowTypes,
+        Mode extends RowMode = "object",
+        SafeIntegers extends boolean = DEFAULT_SAFE_INTEGERS,
+    > {
         database: Database;
         source: string;
-        reader: boolean;
+        reader: Types["reader"];
         readonly: boolean;
         busy: boolean;
 
         run(...params: BindParameters): Database.RunResult;
-        get(...params: BindParameters): Result | undefined;
-        all(...params: BindParameters): Result[];
-        iterate(...params: BindParameters): IterableIterator<Result>;
-        pluck(toggleState?: boolean): this;
-        expand(toggleState?: boolean): this;
-        raw(toggleState?: boolean): this;
+        get(...params: BindParameters): StatementRow<Types, Mode, SafeIntegers> | undefined;
+        all(...params: BindParameters): StatementRow<Types, Mode, SafeIntegers>[];
+        iterate(...params: BindParameters): IterableIterator<StatementRow<Types, Mode, SafeIntegers>>;
+        pluck(toggleState?: true): Statement<BindParameters, Types, "pluck", SafeIntegers>;
+        pluck(toggleState: false): Statement<BindParameters, Types, Mode extends "pluck" ? "object" : Mode, SafeIntegers>;
+        expand(toggleState?: true): Statement<BindParameters, Types, "expand", SafeIntegers>;
+        expand(toggleState: false): Statement<BindParameters, Types, Mode extends "expand" ? "object" : Mode, SafeIntegers>;
+        raw(toggleState?: true): Statement<BindParameters, Types, "raw", SafeIntegers>;
+        raw(toggleState: false): Statement<BindParameters, Types, Mode extends "raw" ? "object" : Mode, SafeIntegers>;
         bind(...params: BindParameters): this;
         columns(): ColumnDefinition[];
+        safeIntegers(toggleState?: true): Statement<BindParameters, Types, Mode, true>;
+        safeIntegers(toggleState: false): Statement<BindParameters, Types, Mode, false>;
+    }
+
+    // Statements that return no data throw on the methods that read rows, so only these are left
+    interface RunStatement<BindParameters extends unknown[]> {
+        database: Database;
+        source: string;
+        reader: false;
+        readonly: boolean;
+        busy: boolean;
+
+        run(...params: BindParameters): Database.RunResult;
+        bind(...params: BindParameters): this;
         safeIntegers(toggleState?: boolean): this;
     }
 
@@ -127,7 +355,11 @@ impl SqlLib for BetterSqlite3 {
 
         prepare<T extends string>(
             source: T
-        ): Statement<T extends keyof Queries ? Queries[T]["args"] extends never ? [] : Queries[T]["args"] : unknown[], T extends keyof Queries ? Queries[T]["returnType"] : unknown>;
+        ): T extends keyof Queries
+            ? Queries[T]["reader"] extends true
+                ? Statement<Queries[T]["args"] extends never ? [] : Queries[T]["args"], Queries[T]>
+                : RunStatement<Queries[T]["args"] extends never ? [] : Queries[T]["args"]>
+            : Statement<unknown[]>;
         transaction<F extends VariableArgFunction>(fn: F): Transaction<F>;
         exec(source: string): this;
         pragma(source: string, options?: Database.PragmaOptions): unknown;
@@ -208,14 +440,22 @@ impl SqlLib for BetterSqlite3 {
 
     const Database: DatabaseConstructor;
     export = Database;
-}"#;
+}"#
+        .replace(
+            "DEFAULT_SAFE_INTEGERS",
+            if self.default_safe_integers {
+                "true"
+            } else {
+                "false"
+            },
+        );
 
         let lexer = Lexer::new(
             Syntax::Typescript(TsSyntax {
                 ..Default::default()
             }),
             Default::default(),
-            StringInput::new(suffix, BytePos(0), BytePos(suffix.len() as u32)),
+            StringInput::new(&suffix, BytePos(0), BytePos(suffix.len() as u32)),
             None,
         );
         let mut parser = Parser::new_from(lexer);
@@ -0,0 +1,157 @@
+use crate::sql_libs::better_sqlite3::{sqlite_declared_type_to_ts_type, sqlite_type_to_ts_type};
+use crate::sql_libs::{Binding, Bindings, SqlLib};
+use crate::utils::ast::query_string;
+use crate::utils::sql_placeholders::{Placeholder, placeholders};
+use crate::utils::ts_types::ts_type_ref;
+use std::collections::HashMap;
+use swc_common::BytePos;
+use swc_ecma_ast::{CallExpr, Expr, ModuleItem, TsType};
+use swc_ecma_parser::{Lexer, Parser, StringInput, Syntax, TsSyntax};
+
+const METHODS: [&str; 10] = [
+    "getAllAsync",
+    "getFirstAsync",
+    "getEachAsync",
+    "runAsync",
+    "prepareAsync",
+    "getAllSync",
+    "getFirstSync",
+    "getEachSync",
+    "runSync",
+    "prepareSync",
+];
+
+/// The `SQLiteDatabase` API of `expo-sqlite`
+pub struct ExpoSqlite;
+
+/// Maps the `:name` and `$name` placeholders of a query onto the keys of a bind object, which keep their
+/// prefix. SQLite numbers named parameters by their first occurrence, so repeated names share a parameter.
+/// Returns `None` if the parameters are positional.
+pub(super) fn sqlite_named_bindings(params_expr: Option<&Expr>, query: &str) -> Option<Bindings> {
+    let found = placeholders(query);
+    let named = match params_expr {
+        Some(Expr::Object(_)) => true,
+        Some(Expr::Array(_)) | None => false,
+        Some(_) => found.iter().any(|(_, placeholder)| {
+            matches!(placeholder, Placeholder::Named(_) | Placeholder::Dollar(_))
+        }),
+    };
+    if !named {
+        return None;
+    }
+
+    let mut bindings = Vec::new();
+    let mut parameter_indices: HashMap<&str, usize> = HashMap::new();
+    for (range, placeholder) in found {
+        if let Placeholder::Named(_) | Placeholder::Dollar(_) = placeholder {
+            let key = &query[range];
+            let next_index = parameter_indices.len();
+            let index = *parameter_indices.entry(key).or_insert(next_index);
+            bindings.push((key.to_string(), Binding::Parameter(index)));
+        }
+    }
+
+    Some(Bindings::Named(bindings))
+}
+
+impl SqlLib for ExpoSqlite {
+    type Db = sqlx::Sqlite;
+
+    fn parse_call_expr(&self, call_expr: &CallExpr) -> Option<String> {
+        let swc_ecma_ast::Callee::Expr(expr) = &call_expr.callee else {
+            return None;
+        };
+
+        let swc_ecma_ast::Expr::Member(member_expr) = &**expr else {
+            return None;
+        };
+
+        let obj = &member_expr.obj.as_ident()?.sym;
+        let prop = &member_expr.prop.as_ident()?.sym;
+        if obj != "db" || !METHODS.contains(&&**prop) {
+            return None;
+        }
+
+        // The params can be passed as an array, an object or spread over the remaining arguments
+        let query_expr = call_expr.args.first()?;
+        if query_expr.spread.is_some() {
+            return None;
+        }
+
+        let query = query_string(&query_expr.expr)?;
+
+        Some(query)
+    }
+
+    fn rewrite_query(
+        &self,
+        call_expr: &CallExpr,
+        query: &str,
+    ) -> Option<(String, Option<Bindings>)> {
+        let params_expr = call_expr.args.get(1).map(|arg| &*arg.expr);
+        Some((query.to_string(), sqlite_named_bindings(params_expr, query)))
+    }
+
+    fn db_type_to_ts_type(&self, ty: &<Self::Db as sqlx::Database>::TypeInfo) -> TsType {
+        sqlite_type_to_ts_type(ty, false, ts_type_ref("Uint8Array"))
+    }
+
+    fn declared_type_to_ts_type(&self, declared_type: &str, strict: bool) -> Option<TsType> {
+        Some(sqlite_declared_type_to_ts_type(
+            declared_type,
+            strict,
+            false,
+            ts_type_ref("Uint8Array"),
+        ))
+    }
+
+    fn d_ts_prefix(&self) -> Vec<ModuleItem> {
+        let prefix = r#"import type { SQLiteExecuteAsyncResult, SQLiteExecuteSyncResult, SQLiteRunResult, SQLiteStatement } from "expo-sqlite";
+type BindParams<Args> = [Args] extends [never] ? [] : Args extends unknown[] ? Args | [Args] : [Args];
+type TypedStatement<Args, Row> = Omit<SQLiteStatement, "executeAsync" | "executeSync"> & {
+    executeAsync(...params: BindParams<Args>): Promise<SQLiteExecuteAsyncResult<Row>>;
+    executeSync(...params: BindParams<Args>): SQLiteExecuteSyncResult<Row>;
+};
+"#;
+        let lexer = Lexer::new(
+            Syntax::Typescript(TsSyntax {
+                ..Default::default()
+            }),
+            Default::default(),
+            StringInput::new(prefix, BytePos(0), BytePos(prefix.len() as u32)),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+        parser.parse_typescript_module().unwrap().body
+    }
+
+    fn d_ts_suffix(&self) -> Vec<ModuleItem> {
+        // `SQLiteDatabase` is re-exported by `expo-sqlite`, so we augment the module that declares it
+        let suffix = r#"declare module "expo-sqlite/build/SQLiteDatabase" {
+    interface SQLiteDatabase {
+        getAllAsync<T extends keyof Queries>(source: T, ...params: BindParams<Queries[T]["args"]>): Promise<Queries[T]["returnType"][]>;
+        getFirstAsync<T extends keyof Queries>(source: T, ...params: BindParams<Queries[T]["args"]>): Promise<Queries[T]["returnType"] | null>;
+        getEachAsync<T extends keyof Queries>(source: T, ...params: BindParams<Queries[T]["args"]>): AsyncIterableIterator<Queries[T]["returnType"]>;
+        runAsync<T extends keyof Queries>(source: T, ...params: BindParams<Queries[T]["args"]>): Promise<SQLiteRunResult>;
+        prepareAsync<T extends keyof Queries>(source: T): Promise<TypedStatement<Queries[T]["args"], Queries[T]["returnType"]>>;
+        getAllSync<T extends keyof Queries>(source: T, ...params: BindParams<Queries[T]["args"]>): Queries[T]["returnType"][];
+        getFirstSync<T extends keyof Queries>(source: T, ...params: BindParams<Queries[T]["args"]>): Queries[T]["returnType"] | null;
+        getEachSync<T extends keyof Queries>(source: T, ...params: BindParams<Queries[T]["args"]>): IterableIterator<Queries[T]["returnType"]>;
+        runSync<T extends keyof Queries>(source: T, ...params: BindParams<Queries[T]["args"]>): SQLiteRunResult;
+        prepareSync<T extends keyof Queries>(source: T): TypedStatement<Queries[T]["args"], Queries[T]["returnType"]>;
+    }
+}
+"#;
+        let lexer = Lexer::new(
+            Syntax::Typescript(TsSyntax {
+                no_early_errors: true,
+                ..Default::default()
+            }),
+            Default::default(),
+            StringInput::new(suffix, BytePos(0), BytePos(suffix.len() as u32)),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+        parser.parse_typescript_module().unwrap().body
+    }
+}
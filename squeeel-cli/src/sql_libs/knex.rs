@@ -0,0 +1,236 @@
+use crate::Dialect;
+use crate::describe::DbExt;
+use crate::sql_libs::{Binding, Bindings, SqlLib};
+use crate::utils::ast::{array_string_element, find_config_prop, object_string_prop, query_string};
+use crate::utils::constants::JSON_VALUE_TYPE;
+use crate::utils::sql_placeholders::{Placeholder, placeholders, replace_placeholders};
+use std::collections::HashMap;
+use swc_common::BytePos;
+use swc_ecma_ast::{CallExpr, Expr, Module, ModuleItem, TsType};
+use swc_ecma_parser::{Lexer, Parser, StringInput, Syntax, TsSyntax};
+
+/// `knex.raw(...)` queries. Knex is only a query builder, so the types of the columns are decided by the
+/// driver it is configured with.
+pub struct Knex<Driver> {
+    driver: Driver,
+}
+
+impl<Driver> Knex<Driver> {
+    pub fn new(driver: Driver) -> Self {
+        Self { driver }
+    }
+}
+
+impl<Driver: SqlLib> Knex<Driver> {
+    fn dialect(&self) -> Dialect {
+        <Driver::Db as DbExt>::DIALECT
+    }
+}
+
+/// Rewrites knex bindings (`?`, `??`, `:name` and `:name:`) into the placeholders of the dialect, and
+/// interpolates identifier bindings that are given as string literals at the call site.
+pub(super) fn rewrite_knex_bindings(
+    dialect: Dialect,
+    bindings_expr: Option<&Expr>,
+    query: &str,
+) -> Option<(String, Option<Bindings>)> {
+    // Knex decides between positional and named bindings based on whether the bindings are an array
+    // or an object. If we can't tell from the call site, we look at the placeholders instead.
+    let named = match bindings_expr {
+        Some(Expr::Object(_)) => true,
+        Some(Expr::Array(_)) => false,
+        _ => placeholders(query).iter().any(|(_, placeholder)| {
+            matches!(
+                placeholder,
+                Placeholder::Named(_) | Placeholder::NamedIdentifier(_)
+            )
+        }),
+    };
+
+    if named {
+        let mut bindings = Vec::new();
+        let mut parameter_indices: HashMap<&str, usize> = HashMap::new();
+        let mut parameter_count = 0;
+        let sql = replace_placeholders(query, |placeholder, original| match placeholder {
+            Placeholder::Named(name) => {
+                let index = match dialect {
+                    // Postgres can reuse a numbered parameter, the other dialects need one per occurrence
+                    Dialect::Postgres => *parameter_indices.entry(name).or_insert_with(|| {
+                        parameter_count += 1;
+                        parameter_count - 1
+                    }),
+                    Dialect::MySql | Dialect::Sqlite => {
+                        parameter_count += 1;
+                        parameter_count - 1
+                    }
+                };
+                bindings.push((name.to_string(), Binding::Parameter(index)));
+                Some(dialect.placeholder(index))
+            }
+            Placeholder::NamedIdentifier(name) => {
+                let identifier = object_string_prop(bindings_expr, name)?;
+                let quoted = dialect.quote_identifier(&identifier);
+                bindings.push((name.to_string(), Binding::Identifier(identifier)));
+                Some(quoted)
+            }
+            Placeholder::Question
+            | Placeholder::DoubleQuestion
+            | Placeholder::EscapedQuestion
+            | Placeholder::Dollar(_) => Some(original.to_string()),
+        })?;
+
+        Some((sql, Some(Bindings::Named(bindings))))
+    } else {
+        let mut bindings = Vec::new();
+        let mut parameter_count = 0;
+        let sql = replace_placeholders(query, |placeholder, original| match placeholder {
+            Placeholder::Question => {
+                bindings.push(Binding::Parameter(parameter_count));
+                parameter_count += 1;
+                Some(dialect.placeholder(parameter_count - 1))
+            }
+            Placeholder::DoubleQuestion => {
+                let identifier = array_string_element(bindings_expr, bindings.len())?;
+                let quoted = dialect.quote_identifier(&identifier);
+                bindings.push(Binding::Identifier(identifier));
+                Some(quoted)
+            }
+            Placeholder::EscapedQuestion => Some("?".to_string()),
+            Placeholder::Named(_) | Placeholder::NamedIdentifier(_) | Placeholder::Dollar(_) => {
+                Some(original.to_string())
+            }
+        })?;
+
+        Some((sql, Some(Bindings::Positional(bindings))))
+    }
+}
+
+impl<Driver: SqlLib> SqlLib for Knex<Driver> {
+    type Db = Driver::Db;
+
+    fn parse_call_expr(&self, call_expr: &CallExpr) -> Option<String> {
+        let swc_ecma_ast::Callee::Expr(expr) = &call_expr.callee else {
+            return None;
+        };
+
+        let swc_ecma_ast::Expr::Member(member_expr) = &**expr else {
+            return None;
+        };
+
+        let obj = &member_expr.obj.as_ident()?.sym;
+        let prop = &member_expr.prop.as_ident()?.sym;
+        if obj != "knex" || prop != "raw" {
+            return None;
+        }
+
+        let mut args_iter = call_expr.args.iter();
+        let query_expr = args_iter.next()?;
+        let _bindings = args_iter.next();
+        if args_iter.next().is_some() {
+            return None;
+        }
+
+        if query_expr.spread.is_some() {
+            return None;
+        }
+
+        let query = query_string(&query_expr.expr)?;
+
+        Some(query)
+    }
+
+    fn rewrite_query(
+        &self,
+        call_expr: &CallExpr,
+        query: &str,
+    ) -> Option<(String, Option<Bindings>)> {
+        rewrite_knex_bindings(
+            self.dialect(),
+            call_expr.args.get(1).map(|arg| &*arg.expr),
+            query,
+        )
+    }
+
+    fn db_type_to_ts_type(
+        &self,
+        ty: &<<Self::Db as DbExt>::Db as sqlx::Database>::TypeInfo,
+    ) -> TsType {
+        self.driver.db_type_to_ts_type(ty)
+    }
+
+    fn declared_type_to_ts_type(&self, declared_type: &str, strict: bool) -> Option<TsType> {
+        self.driver.declared_type_to_ts_type(declared_type, strict)
+    }
+
+    fn db_type_to_input_ts_type(
+        &self,
+        ty: &<<Self::Db as DbExt>::Db as sqlx::Database>::TypeInfo,
+    ) -> TsType {
+        self.driver.db_type_to_input_ts_type(ty)
+    }
+
+    fn declared_type_to_input_ts_type(&self, declared_type: &str, strict: bool) -> Option<TsType> {
+        self.driver
+            .declared_type_to_input_ts_type(declared_type, strict)
+    }
+
+    fn d_ts_prefix(&self) -> Vec<ModuleItem> {
+        let prefix = format!(
+            r#"import type {{ Knex }} from "knex";
+{JSON_VALUE_TYPE}
+"#
+        );
+        let lexer = Lexer::new(
+            Syntax::Typescript(TsSyntax {
+                ..Default::default()
+            }),
+            Default::default(),
+            StringInput::new(&prefix, BytePos(0), BytePos(prefix.len() as u32)),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+        parser.parse_typescript_module().unwrap().body
+    }
+
+    fn d_ts_suffix(&self) -> Vec<ModuleItem> {
+        // The awaited result of `knex.raw` is whatever the underlying driver returns
+        let result = match self.dialect() {
+            Dialect::Postgres => r#"{ rows: Queries[T]["returnType"][] }"#,
+            Dialect::MySql => r#"[Queries[T]["returnType"][], unknown[]]"#,
+            Dialect::Sqlite => r#"Queries[T]["returnType"][]"#,
+        };
+        let suffix = format!(
+            r#"declare module "knex" {{
+    namespace Knex {{
+        interface RawBuilder<TRecord extends {{}} = any, TResult = any> {{
+            <T extends string>(
+                ...params: T extends keyof Queries ?
+                    Queries[T]["args"] extends never ?
+                        [sql: T] :
+                        [sql: T, bindings: Queries[T]["args"]] :
+                    [sql: T, bindings?: any]
+            ): Raw<T extends keyof Queries ? {result} : TResult>;
+        }}
+    }}
+}}
+"#
+        );
+        let lexer = Lexer::new(
+            Syntax::Typescript(TsSyntax {
+                no_early_errors: true,
+                ..Default::default()
+            }),
+            Default::default(),
+            StringInput::new(&suffix, BytePos(0), BytePos(suffix.len() as u32)),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+        parser.parse_typescript_module().unwrap().body
+    }
+}
+
+/// Finds the knex `client` configured in a module, either through a `knex({ client })` call, or, if the
+/// module is a knexfile, through any `client` property, as every environment has its own config object.
+pub fn detect_knex_client(module: &Module, is_knexfile: bool) -> Option<String> {
+    find_config_prop(module, &["knex", "Knex"], "client", is_knexfile)
+}
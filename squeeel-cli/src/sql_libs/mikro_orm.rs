@@ -0,0 +1,158 @@
+use crate::describe::DbExt;
+use crate::sql_libs::knex::rewrite_knex_bindings;
+use crate::sql_libs::{Bindings, SqlLib};
+use crate::utils::ast::{find_config_prop, query_string, trailing_name};
+use crate::utils::constants::JSON_VALUE_TYPE;
+use swc_common::BytePos;
+use swc_ecma_ast::{CallExpr, Module, ModuleItem, TsType};
+use swc_ecma_parser::{Lexer, Parser, StringInput, Syntax, TsSyntax};
+
+/// Raw `em.execute(...)` calls on a MikroORM `EntityManager`. MikroORM runs these through knex, so the
+/// bindings follow knex, while the column types are those of the driver.
+pub struct MikroOrm<Driver> {
+    driver: Driver,
+}
+
+impl<Driver> MikroOrm<Driver> {
+    pub fn new(driver: Driver) -> Self {
+        Self { driver }
+    }
+}
+
+impl<Driver: SqlLib> SqlLib for MikroOrm<Driver> {
+    type Db = Driver::Db;
+
+    fn parse_call_expr(&self, call_expr: &CallExpr) -> Option<String> {
+        let swc_ecma_ast::Callee::Expr(expr) = &call_expr.callee else {
+            return None;
+        };
+
+        let swc_ecma_ast::Expr::Member(member_expr) = &**expr else {
+            return None;
+        };
+
+        let obj = trailing_name(&member_expr.obj)?;
+        let prop = &member_expr.prop.as_ident()?.sym;
+        if !["em", "entityManager"].contains(&obj) || prop != "execute" {
+            return None;
+        }
+
+        // `em.execute(sql, params, method, loggerContext)`
+        let mut args_iter = call_expr.args.iter();
+        let query_expr = args_iter.next()?;
+        let _params = args_iter.next();
+        let method = args_iter.next();
+        let _logger_context = args_iter.next();
+        if args_iter.next().is_some() {
+            return None;
+        }
+
+        // `run` returns a `QueryResult` instead of rows, which we don't type
+        if let Some(method) = method {
+            let method = &method.expr.as_lit()?.as_str()?.value;
+            if !matches!(&**method, "all" | "get") {
+                return None;
+            }
+        }
+
+        if query_expr.spread.is_some() {
+            return None;
+        }
+
+        let query = query_string(&query_expr.expr)?;
+
+        Some(query)
+    }
+
+    fn rewrite_query(
+        &self,
+        call_expr: &CallExpr,
+        query: &str,
+    ) -> Option<(String, Option<Bindings>)> {
+        rewrite_knex_bindings(
+            <Driver::Db as DbExt>::DIALECT,
+            call_expr.args.get(1).map(|arg| &*arg.expr),
+            query,
+        )
+    }
+
+    fn db_type_to_ts_type(
+        &self,
+        ty: &<<Self::Db as DbExt>::Db as sqlx::Database>::TypeInfo,
+    ) -> TsType {
+        self.driver.db_type_to_ts_type(ty)
+    }
+
+    fn declared_type_to_ts_type(&self, declared_type: &str, strict: bool) -> Option<TsType> {
+        self.driver.declared_type_to_ts_type(declared_type, strict)
+    }
+
+    fn db_type_to_input_ts_type(
+        &self,
+        ty: &<<Self::Db as DbExt>::Db as sqlx::Database>::TypeInfo,
+    ) -> TsType {
+        self.driver.db_type_to_input_ts_type(ty)
+    }
+
+    fn declared_type_to_input_ts_type(&self, declared_type: &str, strict: bool) -> Option<TsType> {
+        self.driver
+            .declared_type_to_input_ts_type(declared_type, strict)
+    }
+
+    fn d_ts_prefix(&self) -> Vec<ModuleItem> {
+        let prefix = format!(
+            r#"import type {{ AbstractSqlDriver }} from "@mikro-orm/knex";
+{JSON_VALUE_TYPE}
+"#
+        );
+        let lexer = Lexer::new(
+            Syntax::Typescript(TsSyntax {
+                ..Default::default()
+            }),
+            Default::default(),
+            StringInput::new(&prefix, BytePos(0), BytePos(prefix.len() as u32)),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+        parser.parse_typescript_module().unwrap().body
+    }
+
+    fn d_ts_suffix(&self) -> Vec<ModuleItem> {
+        let suffix = r#"declare module "@mikro-orm/knex/SqlEntityManager" {
+    interface SqlEntityManager<Driver extends AbstractSqlDriver = AbstractSqlDriver> {
+        execute<T extends string>(
+            ...params: T extends keyof Queries ?
+                Queries[T]["args"] extends never ?
+                    [sql: T, params?: [], method?: "all"] :
+                    [sql: T, params: Queries[T]["args"], method?: "all"] :
+                [sql: T, params?: any[], method?: "all"]
+        ): Promise<T extends keyof Queries ? Queries[T]["returnType"][] : any>;
+        execute<T extends string>(
+            ...params: T extends keyof Queries ?
+                Queries[T]["args"] extends never ?
+                    [sql: T, params: [], method: "get"] :
+                    [sql: T, params: Queries[T]["args"], method: "get"] :
+                [sql: T, params: any[], method: "get"]
+        ): Promise<T extends keyof Queries ? Queries[T]["returnType"] : any>;
+    }
+}
+"#;
+        let lexer = Lexer::new(
+            Syntax::Typescript(TsSyntax {
+                no_early_errors: true,
+                ..Default::default()
+            }),
+            Default::default(),
+            StringInput::new(suffix, BytePos(0), BytePos(suffix.len() as u32)),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+        parser.parse_typescript_module().unwrap().body
+    }
+}
+
+/// Finds the `type` configured through `MikroORM.init({ type })` or `defineConfig({ type })`, which is how
+/// the driver was chosen before MikroORM split the drivers into their own packages.
+pub fn detect_mikro_orm_type(module: &Module) -> Option<String> {
+    find_config_prop(module, &["init", "defineConfig"], "type", false)
+}
@@ -1,39 +1,162 @@
 mod better_sqlite3;
+mod expo_sqlite;
+mod knex;
+mod mikro_orm;
+mod mysql;
 mod mysql2;
 mod node_postgres;
-use crate::describe::DbExt;
+mod planetscale;
+mod sequelize;
+mod sql_js;
+mod typeorm;
+use crate::config::{Config, EnumValues, TypeImport};
+use crate::describe::{ColumnInfo, DbExt, EnumType};
+use crate::utils::param_types::{ParamContext, param_contexts};
+use crate::utils::statements::{self, split_statements};
 use crate::utils::ts_types::{
-    TS_NEVER_TYPE, TS_UNKNOWN_TYPE, ts_object_type, ts_object_type_computed, ts_optional_type,
-    ts_tuple_type,
+    TS_NEVER_TYPE, TS_NUMBER_TYPE, TS_STRING_TYPE, TS_UNKNOWN_TYPE, parse_ts_type,
+    ts_bool_literal_type, ts_labeled_tuple_type, ts_nullable_type, ts_object_type,
+    ts_object_type_computed, ts_optional_type, ts_string_literal_type, ts_template_literal,
+    ts_tuple_type, ts_type_name,
 };
-use crate::visitor::Query;
-use sqlx::{Column, Either};
+use crate::visitor::{Query, QueryLocation};
+use serde::Deserialize;
+use sqlx::{Column, Either, TypeInfo};
 use sqlx_core::describe::Describe;
-use swc_common::Span;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use swc_common::{BytePos, Span};
 use swc_ecma_ast::{
-    CallExpr, Decl, Expr, Ident, Module, ModuleItem, Stmt, Tpl, TplElement, TsType, TsTypeAliasDecl,
+    CallExpr, Decl, ExportDecl, Expr, Ident, Module, ModuleDecl, ModuleItem, Stmt, TsType,
+    TsTypeAliasDecl,
 };
+use swc_ecma_parser::{Lexer, Parser, StringInput, Syntax, TsSyntax};
+
+pub use knex::detect_knex_client;
+pub use mikro_orm::detect_mikro_orm_type;
+pub use mysql2::detect_mysql2_options;
+pub use sequelize::detect_sequelize_dialect;
+pub use typeorm::detect_typeorm_type;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SupportedLib {
     NodePostgres,
     BetterSqlite3,
     MySql2,
+    MySql,
+    Planetscale,
+    ExpoSqlite,
+    SqlJs,
+    Knex(Dialect),
+    TypeOrm(Dialect),
+    MikroOrm(Dialect),
+    Sequelize(Dialect),
 }
 
-#[derive(PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Dialect {
     Postgres,
     MySql,
     Sqlite,
 }
 
+impl Dialect {
+    /// The dialect of a database as it is named by a driver package, a knex `client`, a TypeORM `type` or a
+    /// MikroORM driver
+    pub fn from_client_name(name: &str) -> Option<Self> {
+        match name {
+            "pg" | "postgres" | "postgresql" | "pg-native" | "pgnative" | "cockroachdb"
+            | "redshift" | "aurora-postgres" => Some(Dialect::Postgres),
+            "mysql" | "mysql2" | "mariadb" | "aurora-mysql" => Some(Dialect::MySql),
+            "sqlite" | "sqlite3" | "better-sqlite3" | "better-sqlite" | "libsql" | "sqljs"
+            | "capacitor" | "cordova" | "react-native" | "nativescript" | "expo" => {
+                Some(Dialect::Sqlite)
+            }
+            _ => None,
+        }
+    }
+
+    /// The dialect of an installed driver package
+    pub fn from_driver_package(package: &str) -> Option<Self> {
+        match package {
+            "pg" | "pg-native" | "@mikro-orm/postgresql" => Some(Dialect::Postgres),
+            "mysql" | "mysql2" | "mariadb" | "@mikro-orm/mysql" | "@mikro-orm/mariadb" => {
+                Some(Dialect::MySql)
+            }
+            "sqlite3"
+            | "better-sqlite3"
+            | "expo-sqlite"
+            | "sql.js"
+            | "@mikro-orm/sqlite"
+            | "@mikro-orm/better-sqlite"
+            | "@mikro-orm/libsql" => Some(Dialect::Sqlite),
+            _ => None,
+        }
+    }
+
+    /// The placeholder for the parameter with the given (zero based) index
+    pub fn placeholder(&self, index: usize) -> String {
+        match self {
+            Dialect::Postgres => format!("${}", index + 1),
+            Dialect::MySql | Dialect::Sqlite => "?".to_string(),
+        }
+    }
+
+    /// Quotes a possibly qualified identifier, e.g. `users.id` becomes `"users"."id"`
+    pub fn quote_identifier(&self, identifier: &str) -> String {
+        let quote = match self {
+            Dialect::MySql => '`',
+            Dialect::Postgres | Dialect::Sqlite => '"',
+        };
+        identifier
+            .split('.')
+            .map(|part| {
+                if part == "*" {
+                    part.to_string()
+                } else {
+                    format!("{quote}{part}{quote}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+}
+
+/// How the arguments passed at the call site map onto the parameters of the described query
+#[derive(Debug, Clone)]
+pub enum Bindings {
+    /// The arguments are passed as an array
+    Positional(Vec<Binding>),
+    /// The arguments are passed as an object, keyed by the placeholder names
+    Named(Vec<(String, Binding)>),
+    /// The arguments are passed as properties of an options object, each with their own bindings
+    Options(Vec<(String, Bindings)>),
+}
+
+#[derive(Debug, Clone)]
+pub enum Binding {
+    /// The value of the parameter with this index in the described query
+    Parameter(usize),
+    /// An identifier that is interpolated into the query before it is sent to the database
+    Identifier(String),
+    /// An argument that is not referenced by the query
+    Unused,
+}
+
 impl SupportedLib {
     pub fn dialect(&self) -> Dialect {
         match self {
             SupportedLib::NodePostgres => Dialect::Postgres,
-            SupportedLib::BetterSqlite3 => Dialect::Sqlite,
-            SupportedLib::MySql2 => Dialect::MySql,
+            SupportedLib::BetterSqlite3 | SupportedLib::ExpoSqlite | SupportedLib::SqlJs => {
+                Dialect::Sqlite
+            }
+            SupportedLib::MySql2 | SupportedLib::MySql | SupportedLib::Planetscale => {
+                Dialect::MySql
+            }
+            SupportedLib::Knex(dialect)
+            | SupportedLib::TypeOrm(dialect)
+            | SupportedLib::MikroOrm(dialect)
+            | SupportedLib::Sequelize(dialect) => *dialect,
         }
     }
 }
@@ -44,6 +167,14 @@ impl std::fmt::Display for SupportedLib {
             SupportedLib::NodePostgres => write!(f, "pg"),
             SupportedLib::BetterSqlite3 => write!(f, "better-sqlite3"),
             SupportedLib::MySql2 => write!(f, "mysql2"),
+            SupportedLib::MySql => write!(f, "mysql"),
+            SupportedLib::Planetscale => write!(f, "planetscale"),
+            SupportedLib::ExpoSqlite => write!(f, "expo-sqlite"),
+            SupportedLib::SqlJs => write!(f, "sql.js"),
+            SupportedLib::Knex(_) => write!(f, "knex"),
+            SupportedLib::TypeOrm(_) => write!(f, "typeorm"),
+            SupportedLib::MikroOrm(_) => write!(f, "mikro-orm"),
+            SupportedLib::Sequelize(_) => write!(f, "sequelize"),
         }
     }
 }
@@ -56,6 +187,10 @@ impl TryFrom<String> for SupportedLib {
             "pg" => Ok(SupportedLib::NodePostgres),
             "better-sqlite3" => Ok(SupportedLib::BetterSqlite3),
             "mysql2" => Ok(SupportedLib::MySql2),
+            "mysql" => Ok(SupportedLib::MySql),
+            "@planetscale/database" => Ok(SupportedLib::Planetscale),
+            "expo-sqlite" => Ok(SupportedLib::ExpoSqlite),
+            "sql.js" => Ok(SupportedLib::SqlJs),
             _ => Err(()),
         }
     }
@@ -64,8 +199,32 @@ impl TryFrom<String> for SupportedLib {
 trait SqlLib {
     type Db: DbExt;
 
+    /// Whether the lib can return rows as arrays of values in column order, in which case the queries also get
+    /// a `rawReturnType` tuple next to their `returnType`
+    const RAW_ROWS: bool = false;
+
+    /// Whether the lib can run several statements in one query string, in which case such queries are typed
+    /// by a `statements` tuple with the type of each statement instead of a `returnType`
+    const MULTIPLE_STATEMENTS: bool = false;
+
+    /// Why the lib doesn't run the statements of this query with several of them, for libs that only run
+    /// several statements in some cases. Such queries are described as one statement.
+    fn multiple_statements_unsupported(&self, _sql: &str) -> Option<&'static str> {
+        None
+    }
+
     fn parse_call_expr(&self, call_expr: &CallExpr) -> Option<String>;
 
+    /// Rewrites the query into sql the database can describe, for libs that have their own placeholder
+    /// syntax. Returns `None` if the query can not be described without knowing the runtime values.
+    fn rewrite_query(
+        &self,
+        _call_expr: &CallExpr,
+        query: &str,
+    ) -> Option<(String, Option<Bindings>)> {
+        Some((query.to_string(), None))
+    }
+
     fn d_ts_prefix(&self) -> Vec<ModuleItem>;
 
     fn d_ts_suffix(&self) -> Vec<ModuleItem>;
@@ -74,54 +233,425 @@ trait SqlLib {
         &self,
         ty: &<<Self::Db as DbExt>::Db as sqlx::Database>::TypeInfo,
     ) -> TsType;
+
+    /// The TS type of a table column from the type it is declared with, for databases where the declared
+    /// type only decides how values are stored, like SQLite. `None` means the type of the described column
+    /// is used instead.
+    fn declared_type_to_ts_type(&self, _declared_type: &str, _strict: bool) -> Option<TsType> {
+        None
+    }
+
+    /// The TS type of a parameter of a database type. Drivers accept more than they return, like a `string`
+    /// for a date, so this can be wider than the type of a column.
+    fn db_type_to_input_ts_type(
+        &self,
+        ty: &<<Self::Db as DbExt>::Db as sqlx::Database>::TypeInfo,
+    ) -> TsType {
+        self.db_type_to_ts_type(ty)
+    }
+
+    /// The TS type of a parameter that is bound to a table column with the given declared type
+    fn declared_type_to_input_ts_type(&self, declared_type: &str, strict: bool) -> Option<TsType> {
+        self.declared_type_to_ts_type(declared_type, strict)
+    }
+
+    /// Extra members of the type of each query, for libs that can return the rows in other shapes, or that
+    /// return something else than rows depending on the kind of statement
+    fn query_type_members(
+        &self,
+        _config: &Config,
+        _describe: &Describe<<Self::Db as DbExt>::Db>,
+        _column_info: &[ColumnInfo],
+        _kind: &StatementKind,
+    ) -> Vec<(Expr, TsType, bool)> {
+        Vec::new()
+    }
 }
 
-fn describe_to_d_ts_query<Lib: SqlLib>(
+/// The TS type of a database type, unless it is overridden in the config. With `input`, it is the type of a
+/// parameter rather than of a returned value.
+fn db_type_to_ts_type<Lib: SqlLib>(
+    lib: &Lib,
+    config: &Config,
+    ty: &<<Lib::Db as DbExt>::Db as sqlx::Database>::TypeInfo,
+    input: bool,
+) -> TsType {
+    match config.type_override(ty.name(), Lib::Db::type_oid(ty)) {
+        Some(type_override) => type_override.ts_type(),
+        None if input => lib.db_type_to_input_ts_type(ty),
+        None => lib.db_type_to_ts_type(ty),
+    }
+}
+
+/// The TS type of a column from its declared type, for databases where that is what decides how the values
+/// are stored. The type overrides are looked up by the declared type without its arguments, e.g. `varchar`
+/// for `VARCHAR(255)`.
+fn declared_type_to_ts_type<Lib: SqlLib>(
+    lib: &Lib,
+    config: &Config,
+    declared_type: &str,
+    strict: bool,
+    input: bool,
+) -> Option<TsType> {
+    let name = declared_type.split('(').next().unwrap_or_default().trim();
+    match config.type_override(name, None) {
+        Some(type_override) => Some(type_override.ts_type()),
+        None if input => lib.declared_type_to_input_ts_type(declared_type, strict),
+        None => lib.declared_type_to_ts_type(declared_type, strict),
+    }
+}
+
+/// The overrides in a column alias, following the convention of sqlx: `"id!"` is never null, `"id?"` can
+/// be null, and `"id: UserId"` has the TS type `UserId`, which can be combined as `"id!: UserId"`.
+struct AliasOverride<'a> {
+    /// The name without the overrides, like `id`
+    name: &'a str,
+    nullable: Option<bool>,
+    ts_type: Option<TsType>,
+}
+
+fn alias_override(alias: &str) -> AliasOverride<'_> {
+    let no_override = AliasOverride {
+        name: alias,
+        nullable: None,
+        ts_type: None,
+    };
+    // Names like `?column?` and `?` are given by the database, not overrides
+    if alias.starts_with('?') {
+        return no_override;
+    }
+    let (name, ts_type) = match alias.split_once(':') {
+        Some((name, ty)) => match parse_ts_type(ty.trim()) {
+            Some(ts_type) => (name.trim_end(), Some(ts_type)),
+            None => return no_override,
+        },
+        None => (alias, None),
+    };
+    let (name, nullable) = if let Some(name) = name.strip_suffix('!') {
+        (name, Some(false))
+    } else if let Some(name) = name.strip_suffix('?') {
+        (name, Some(true))
+    } else {
+        (name, None)
+    };
+    AliasOverride {
+        name,
+        nullable,
+        ts_type,
+    }
+}
+
+/// The TS type of a column, without its nullability: the column override, the type from its declared type, or
+/// the type of the described column. With `input`, it is the type of a parameter bound to the column.
+fn column_ts_type<Lib: SqlLib>(
+    lib: &Lib,
+    config: &Config,
+    type_info: &<<Lib::Db as DbExt>::Db as sqlx::Database>::TypeInfo,
+    info: &ColumnInfo,
+    input: bool,
+) -> TsType {
+    let column_override = info
+        .origin
+        .as_ref()
+        .and_then(|origin| config.column_override(origin));
+    let declared_type = info.declared_type.as_deref().and_then(|declared_type| {
+        declared_type_to_ts_type(lib, config, declared_type, info.strict, input)
+    });
+    match (column_override, declared_type) {
+        (Some(column_override), _) => column_override.ts_type(),
+        (None, Some(declared_type)) => declared_type,
+        (None, None) => db_type_to_ts_type(lib, config, type_info, input),
+    }
+}
+
+fn describe_to_column_types<Lib: SqlLib>(
     lib: &Lib,
+    config: &Config,
     describe: &Describe<<Lib::Db as DbExt>::Db>,
-) -> (TsType, TsType) {
-    let mut return_type_members: Vec<(Expr, TsType, bool)> =
-        Vec::with_capacity(describe.columns.len());
-    for i in 0..describe.columns.len() {
+    column_info: &[ColumnInfo],
+) -> Vec<(Expr, TsType, bool)> {
+    let mut columns: Vec<(Expr, TsType, bool)> = Vec::with_capacity(describe.columns.len());
+    for (i, info) in column_info.iter().enumerate() {
         let column = &describe.columns[i];
-        let nullable = &describe.nullable[i].unwrap_or(true);
-        let type_info = column.type_info();
-        let ts_type = lib.db_type_to_ts_type(type_info);
-        let final_type = if *nullable {
-            ts_optional_type(ts_type)
-        } else {
-            ts_type
+        // The overrides are stripped from the property name, which the drivers return as the whole alias
+        let alias_override = alias_override(column.name());
+        let nullable = &alias_override
+            .nullable
+            .unwrap_or(describe.nullable[i].unwrap_or(true));
+        let ts_type = match alias_override.ts_type {
+            Some(ts_type) => ts_type,
+            None => column_ts_type(lib, config, column.type_info(), info, false),
+        };
+        // The drivers return `null` for missing values, unless they are wrapped to leave them out
+        let (final_type, optional) = match (*nullable, config.null_as_undefined) {
+            (false, _) => (ts_type, false),
+            (true, false) => (ts_nullable_type(ts_type), false),
+            (true, true) => (ts_optional_type(ts_type), true),
         };
-        return_type_members.push((column.name().into(), final_type, *nullable));
+        columns.push((alias_override.name.into(), final_type, optional));
+    }
+
+    columns
+}
+
+/// The row as an array of values in column order
+fn describe_to_raw_d_ts_type<Lib: SqlLib>(
+    lib: &Lib,
+    config: &Config,
+    describe: &Describe<<Lib::Db as DbExt>::Db>,
+    column_info: &[ColumnInfo],
+) -> TsType {
+    ts_tuple_type(
+        describe_to_column_types(lib, config, describe, column_info)
+            .into_iter()
+            .map(|(_, ts_type, _)| ts_type),
+    )
+}
+
+/// Warnings about result columns that can't be told apart in the row objects: columns with the same name, of
+/// which the drivers only keep the last one, and columns without a name, which Postgres names `?column?`
+fn column_name_warnings<Db: sqlx::Database>(
+    describe: &Describe<Db>,
+    location: &QueryLocation,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let mut reported = HashSet::new();
+    for (i, column) in describe.columns.iter().enumerate() {
+        let name = column.name();
+        if name == "?column?" {
+            warnings.push(format!(
+                "{location}: column {} of the result has no name, so it is named \"?column?\". Give it an alias.",
+                i + 1
+            ));
+        } else if describe.columns[i + 1..]
+            .iter()
+            .any(|other| other.name() == name)
+            && reported.insert(name)
+        {
+            warnings.push(format!(
+                "{location}: the result has several columns named \"{name}\", and only the last one is kept in the rows. Give them different aliases."
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// What kind of statement a query is, which decides what the libs return when it is run
+#[derive(Debug, Clone)]
+pub(crate) struct StatementKind {
+    /// The command of the statement, like `INSERT`, as Postgres reports it. `None` if it can't be told.
+    command: Option<String>,
+    /// Whether the statement returns rows, like a `SELECT` or a write with `RETURNING`
+    reader: bool,
+}
+
+impl StatementKind {
+    /// The `command` member of a query type, typed as a string literal if the command is known
+    fn command_ts_type(&self) -> TsType {
+        match &self.command {
+            Some(command) => ts_string_literal_type(command.as_str()),
+            None => TS_STRING_TYPE,
+        }
+    }
+
+    /// The `reader` member of a query type, a literal that the lib types can branch on
+    fn reader_ts_type(&self) -> TsType {
+        ts_bool_literal_type(self.reader)
+    }
+}
+
+/// What the query tells about a parameter, beyond what the database describes
+#[derive(Debug, Clone, Default)]
+struct InferredParam {
+    /// The type of the parameter, for databases that do not describe it
+    ts_type: Option<TsType>,
+    /// Whether the parameter can be null, because it is assigned to a nullable column or compared with
+    /// `IS NOT DISTINCT FROM`
+    nullable: bool,
+    /// The label of the parameter in the args tuple
+    label: Option<String>,
+}
+
+/// Infers a parameter from where the query uses it. The first use that tells a type decides it, and it can be
+/// null if any use allows that. It is labeled by the name given in a comment, or else by the first column it
+/// is used with.
+fn infer_param(
+    contexts: &[ParamContext],
+    column_types: &HashMap<(String, String), (TsType, bool)>,
+) -> InferredParam {
+    let column_type =
+        |table: &String, column: &String| column_types.get(&(table.clone(), column.to_lowercase()));
+    let ts_type = contexts.iter().find_map(|context| match context {
+        ParamContext::Column { table, column, .. } => {
+            column_type(table, column).map(|(ts_type, _)| ts_type.clone())
+        }
+        ParamContext::Pattern => Some(TS_STRING_TYPE),
+        ParamContext::Count => Some(TS_NUMBER_TYPE),
+        ParamContext::Null | ParamContext::Named(_) => None,
+    });
+    let nullable = contexts.iter().any(|context| match context {
+        ParamContext::Column {
+            table,
+            column,
+            assigned: true,
+        } => column_type(table, column).is_some_and(|(_, nullable)| *nullable),
+        ParamContext::Null => true,
+        _ => false,
+    });
+    let named = contexts.iter().find_map(|context| match context {
+        ParamContext::Named(name) => Some(name),
+        _ => None,
+    });
+    let column = contexts.iter().find_map(|context| match context {
+        ParamContext::Column { column, .. } => Some(column),
+        _ => None,
+    });
+    let label = named
+        .or(column.filter(|column| {
+            column.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$')
+                && column
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+        }))
+        .cloned();
+    InferredParam {
+        ts_type,
+        nullable,
+        label,
     }
+}
+
+/// The args as a tuple, labeled so editors can tell the parameters apart. TS needs every element of a tuple
+/// to be labeled or none, so unnamed elements are labeled by their position, like `$1`, and repeated names
+/// are numbered.
+fn args_tuple_type<Args: IntoIterator<Item = (Option<String>, TsType)>>(args: Args) -> TsType {
+    let mut labels = HashSet::new();
+    ts_labeled_tuple_type(args.into_iter().enumerate().map(|(i, (label, ts_type))| {
+        let label = label.unwrap_or_else(|| format!("${}", i + 1));
+        let label = std::iter::once(label.clone())
+            .chain((2..).map(|n| format!("{label}_{n}")))
+            .find(|label| labels.insert(label.clone()))
+            .unwrap();
+        (label, ts_type)
+    }))
+}
+
+fn describe_to_d_ts_query<Lib: SqlLib>(
+    lib: &Lib,
+    config: &Config,
+    describe: &Describe<<Lib::Db as DbExt>::Db>,
+    column_info: &[ColumnInfo],
+    inferred_params: &[InferredParam],
+    bindings: Option<&Bindings>,
+) -> (TsType, TsType) {
+    let return_type_members = describe_to_column_types(lib, config, describe, column_info);
+    let params = describe_to_params(lib, config, describe, inferred_params);
+
+    (
+        ts_object_type(return_type_members),
+        params_to_args_type(params, bindings),
+    )
+}
 
+/// The labels and types of the parameters of a query
+fn describe_to_params<Lib: SqlLib>(
+    lib: &Lib,
+    config: &Config,
+    describe: &Describe<<Lib::Db as DbExt>::Db>,
+    inferred_params: &[InferredParam],
+) -> Vec<(Option<String>, TsType)> {
     let mut args = Vec::new();
     if let Some(params) = &describe.parameters {
         match params {
             Either::Left(params) => {
                 for param in params.iter() {
-                    let ts_type = lib.db_type_to_ts_type(param);
+                    let ts_type = db_type_to_ts_type(lib, config, param, true);
 
                     args.push(ts_type);
                 }
             }
             Either::Right(count) => {
-                // For a fixed number of parameters, we assume they are all of type `unknown`
-                args = vec![TS_UNKNOWN_TYPE; *count];
+                // The database only tells how many parameters there are, so we use what could be inferred
+                // from the query, and `unknown` for the rest
+                args = (0..*count)
+                    .map(|i| {
+                        inferred_params
+                            .get(i)
+                            .and_then(|param| param.ts_type.clone())
+                            .unwrap_or(TS_UNKNOWN_TYPE)
+                    })
+                    .collect();
             }
         }
     } else {
         // If there are no parameters, we assume an empty tuple
     }
+    args.into_iter()
+        .enumerate()
+        .map(|(i, ts_type)| match inferred_params.get(i) {
+            Some(param) if param.nullable && ts_type != TS_UNKNOWN_TYPE => {
+                (param.label.clone(), ts_nullable_type(ts_type))
+            }
+            Some(param) => (param.label.clone(), ts_type),
+            None => (None, ts_type),
+        })
+        .collect()
+}
 
-    (
-        ts_object_type(return_type_members),
-        if args.is_empty() {
-            TS_NEVER_TYPE
-        } else {
-            ts_tuple_type(args)
-        },
-    )
+/// The `args` type of a query, from its parameters and how the lib binds them
+fn params_to_args_type(
+    params: Vec<(Option<String>, TsType)>,
+    bindings: Option<&Bindings>,
+) -> TsType {
+    match bindings {
+        None if params.is_empty() => TS_NEVER_TYPE,
+        None => args_tuple_type(params),
+        Some(bindings) => bindings_to_ts_type(bindings, &params),
+    }
+}
+
+fn bindings_to_ts_type(bindings: &Bindings, params: &[(Option<String>, TsType)]) -> TsType {
+    match bindings {
+        Bindings::Positional(bindings) if bindings.is_empty() => TS_NEVER_TYPE,
+        Bindings::Positional(bindings) => args_tuple_type(bindings.iter().map(|binding| {
+            let label = match binding {
+                Binding::Parameter(i) => params.get(*i).and_then(|(label, _)| label.clone()),
+                Binding::Identifier(_) | Binding::Unused => None,
+            };
+            (label, binding_to_ts_type(binding, params))
+        })),
+        Bindings::Named(bindings) if bindings.is_empty() => TS_NEVER_TYPE,
+        Bindings::Named(bindings) => {
+            let mut seen = HashSet::new();
+            ts_object_type(bindings.iter().filter(|(name, _)| seen.insert(name)).map(
+                |(name, binding)| {
+                    (
+                        name.as_str().into(),
+                        binding_to_ts_type(binding, params),
+                        false,
+                    )
+                },
+            ))
+        }
+        Bindings::Options(options) => ts_object_type(options.iter().map(|(name, bindings)| {
+            (
+                name.as_str().into(),
+                bindings_to_ts_type(bindings, params),
+                false,
+            )
+        })),
+    }
+}
+
+fn binding_to_ts_type(binding: &Binding, params: &[(Option<String>, TsType)]) -> TsType {
+    match binding {
+        Binding::Parameter(i) => params
+            .get(*i)
+            .map_or(TS_UNKNOWN_TYPE, |(_, ts_type)| ts_type.clone()),
+        Binding::Identifier(identifier) => ts_string_literal_type(identifier.as_str()),
+        Binding::Unused => TS_UNKNOWN_TYPE,
+    }
 }
 
 async fn describe_bulk<Db: DbExt>(queries: Vec<String>) -> Vec<Describe<<Db as DbExt>::Db>> {
@@ -135,97 +665,719 @@ async fn describe_bulk<Db: DbExt>(queries: Vec<String>) -> Vec<Describe<<Db as D
     outputs
 }
 
-async fn create_d_ts_file<Lib: SqlLib>(lib: Lib, queries: Vec<String>) -> Module {
-    let descriptions = describe_bulk::<Lib::Db>(queries.clone()).await;
-    let mut queries_type_members: Vec<(Expr, TsType, bool)> = Vec::with_capacity(queries.len());
+fn parse_ts_module(source: &str) -> Module {
+    let lexer = Lexer::new(
+        Syntax::Typescript(TsSyntax {
+            ..Default::default()
+        }),
+        Default::default(),
+        StringInput::new(source, BytePos(0), BytePos(source.len() as u32)),
+        None,
+    );
+    let mut parser = Parser::new_from(lexer);
+    parser.parse_typescript_module().unwrap()
+}
+
+fn type_import(import: &TypeImport) -> ModuleItem {
+    let source = format!(
+        "import type {{ {} }} from {};",
+        import.name,
+        serde_json::to_string(&import.from).unwrap()
+    );
+    parse_ts_module(&source).body.remove(0)
+}
+
+/// Declares the enums of the database as named types. The type info of an enum only has its unqualified
+/// name, so an enum name that is used in several schemas gets a schema-qualified type per schema, and the
+/// unqualified type is the union of them.
+///
+/// If the config asks for the enum values, they are declared in a separate module that can be used at
+/// runtime, and the types are imported from it. Returns the declarations for the d.ts file, and the
+/// module with the values, if any.
+fn enum_declarations(
+    enums: &[EnumType],
+    enum_values: Option<EnumValues>,
+    enums_module: &str,
+) -> (Vec<ModuleItem>, Option<Module>) {
+    let mut enums_by_name: BTreeMap<&str, Vec<&EnumType>> = BTreeMap::new();
+    for enum_type in enums {
+        enums_by_name
+            .entry(&enum_type.name)
+            .or_default()
+            .push(enum_type);
+    }
+
+    let mut declarations = String::new();
+    let mut names = Vec::new();
+    for (name, enum_types) in enums_by_name {
+        let qualify = enum_types.len() > 1;
+        let mut qualified_names = Vec::new();
+        for enum_type in enum_types {
+            let ts_name = if qualify {
+                ts_type_name(&format!("{}_{name}", enum_type.schema))
+            } else {
+                ts_type_name(name)
+            };
+            declarations.push_str(&enum_declaration(
+                &ts_name,
+                &enum_type.variants,
+                enum_values,
+            ));
+            qualified_names.push(ts_name.clone());
+            names.push(ts_name);
+        }
+        if qualify {
+            let ts_name = ts_type_name(name);
+            declarations.push_str(&format!(
+                "export type {ts_name} = {};\n",
+                qualified_names.join(" | ")
+            ));
+            names.push(ts_name);
+        }
+    }
+
+    if enum_values.is_none() || names.is_empty() {
+        return (parse_ts_module(&declarations).body, None);
+    }
+    let names = names.join(", ");
+    let imports = format!(
+        "import type {{ {names} }} from {};\nexport type {{ {names} }};\n",
+        serde_json::to_string(enums_module).unwrap()
+    );
+    (
+        parse_ts_module(&imports).body,
+        Some(parse_ts_module(&declarations)),
+    )
+}
+
+fn enum_declaration(ts_name: &str, variants: &[String], enum_values: Option<EnumValues>) -> String {
+    let mut keys = HashSet::new();
+    let members = variants.iter().map(|variant| {
+        let value = serde_json::to_string(variant).unwrap();
+        // Fall back to the value itself, and then to a numbered name, if two values have the same name
+        let name = ts_type_name(variant);
+        let key = [name.clone(), variant.clone()]
+            .into_iter()
+            .chain((2..).map(|n| format!("{name}{n}")))
+            .find(|key| keys.insert(key.clone()))
+            .unwrap();
+        let is_identifier = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        let key = if is_identifier {
+            key
+        } else {
+            serde_json::to_string(&key).unwrap()
+        };
+        (key, value)
+    });
+    match enum_values {
+        None if variants.is_empty() => format!("export type {ts_name} = never;\n"),
+        None => format!(
+            "export type {ts_name} = {};\n",
+            variants
+                .iter()
+                .map(|variant| serde_json::to_string(variant).unwrap())
+                .collect::<Vec<_>>()
+                .join(" | ")
+        ),
+        Some(EnumValues::Const) => format!(
+            "export const {ts_name} = {{\n{}}} as const;\nexport type {ts_name} = (typeof {ts_name})[keyof typeof {ts_name}];\n",
+            members
+                .map(|(key, value)| format!("    {key}: {value},\n"))
+                .collect::<String>()
+        ),
+        Some(EnumValues::Enum) => format!(
+            "export enum {ts_name} {{\n{}}}\n",
+            members
+                .map(|(key, value)| format!("    {key} = {value},\n"))
+                .collect::<String>()
+        ),
+    }
+}
+
+async fn create_d_ts_file<Lib: SqlLib>(
+    lib: Lib,
+    queries: Vec<Query>,
+    config: &Config,
+    enums_module: &str,
+) -> (Module, Option<Module>, Vec<String>) {
+    let table_names = Lib::Db::get_table_names().await.unwrap();
+    let table_queries = table_names
+        .iter()
+        .map(|table_name| format!("SELECT * FROM {table_name}"))
+        .collect::<Vec<_>>();
+    let descriptions = describe_bulk::<Lib::Db>(table_queries.clone()).await;
+    let mut table_types: Vec<(Expr, TsType, bool)> = Vec::with_capacity(table_names.len());
+    // The rows that can be inserted into and updated in each table, without the generated columns. Columns that
+    // the database fills in are optional to insert, and every column is optional to update.
+    let mut insertable_types: Vec<(Expr, TsType, bool)> = Vec::with_capacity(table_names.len());
+    let mut updatable_types: Vec<(Expr, TsType, bool)> = Vec::with_capacity(table_names.len());
+    // The columns of each table, with their input types and nullability, to infer the parameters of the
+    // queries from
+    let mut table_columns: HashMap<String, Vec<String>> = HashMap::new();
+    let mut column_types: HashMap<(String, String), (TsType, bool)> = HashMap::new();
     for (i, describe) in descriptions.into_iter().enumerate() {
-        let query = &queries[i];
-        let (return_type, args) = describe_to_d_ts_query(&lib, &describe);
-        queries_type_members.push((
-            Expr::Tpl(Tpl {
-                span: Span::default(),
-                exprs: Vec::new(),
-                quasis: vec![TplElement {
-                    span: Span::default(),
-                    tail: true,
-                    cooked: None,
-                    raw: query.to_string().into(),
-                }],
-            }),
-            ts_object_type([
-                ("returnType".into(), return_type, false),
-                ("args".into(), args, false),
-            ]),
+        let table_name = table_names[i].clone();
+
+        let column_info = Lib::Db::column_info(&table_queries[i], &describe)
+            .await
+            .unwrap();
+        let (return_type, _) =
+            describe_to_d_ts_query(&lib, config, &describe, &column_info, &[], None);
+        table_types.push((table_name.clone().into(), return_type, false));
+        let catalog_columns = Lib::Db::table_columns(&table_name, &describe)
+            .await
+            .unwrap();
+
+        let table = table_name.to_lowercase();
+        let mut insertable_members = Vec::with_capacity(describe.columns.len());
+        let mut updatable_members = Vec::with_capacity(describe.columns.len());
+        for ((column, info), catalog_column) in describe
+            .columns
+            .iter()
+            .zip(&column_info)
+            .zip(&catalog_columns)
+        {
+            let ts_type = column_ts_type(&lib, config, column.type_info(), info, true);
+            let nullable = catalog_column.nullable;
+            if !catalog_column.generated {
+                let ts_type = match nullable {
+                    true => ts_nullable_type(ts_type.clone()),
+                    false => ts_type.clone(),
+                };
+                let optional = nullable || catalog_column.defaulted;
+                insertable_members.push((column.name().into(), ts_type.clone(), optional));
+                updatable_members.push((column.name().into(), ts_type, true));
+            }
+            let key = (table.clone(), column.name().to_lowercase());
+            column_types.insert(key, (ts_type, nullable));
+            table_columns
+                .entry(table.clone())
+                .or_default()
+                .push(column.name().to_string());
+        }
+        insertable_types.push((
+            table_name.clone().into(),
+            ts_object_type(insertable_members),
             false,
         ));
+        updatable_types.push((table_name.into(), ts_object_type(updatable_members), false));
     }
 
-    let table_names = Lib::Db::get_table_names().await.unwrap();
-    let descriptions = describe_bulk::<Lib::Db>(
-        table_names
+    let mut warnings = Vec::new();
+    // Queries with several statements are described one statement at a time, if the lib runs them. Those it
+    // doesn't run are described as one statement on their own, as the database usually can't describe them
+    // either, in which case they are left out.
+    let mut unsupported = vec![false; queries.len()];
+    let statements: Vec<Vec<&str>> = queries
+        .iter()
+        .enumerate()
+        .map(|(i, query)| match split_statements(&query.describe_query) {
+            statements if Lib::MULTIPLE_STATEMENTS && statements.len() > 1 => {
+                match lib.multiple_statements_unsupported(&query.describe_query) {
+                    None => statements,
+                    Some(reason) => {
+                        warnings.push(format!(
+                            "{}: the query has several statements, but {reason}, so it fails when it is run.",
+                            query.location
+                        ));
+                        unsupported[i] = true;
+                        vec![query.describe_query.as_str()]
+                    }
+                }
+            }
+            _ => vec![query.describe_query.as_str()],
+        })
+        .collect();
+    let mut descriptions = describe_bulk::<Lib::Db>(
+        statements
             .iter()
-            .map(|table_name| format!("SELECT * FROM {table_name}"))
-            .collect::<Vec<_>>(),
+            .zip(&unsupported)
+            .filter(|(_, unsupported)| !**unsupported)
+            .flat_map(|(statements, _)| statements)
+            .map(|statement| statement.to_string())
+            .collect(),
     )
-    .await;
-    let mut table_types: Vec<(Expr, TsType, bool)> = Vec::with_capacity(table_names.len());
-    for (i, describe) in descriptions.into_iter().enumerate() {
-        let table_name = table_names[i].clone();
+    .await
+    .into_iter();
+    let mut queries_type_members: Vec<(Expr, TsType, bool)> = Vec::with_capacity(queries.len());
+    // The `Row` and `Params` types of the queries that are named, by a comment or the variable they are
+    // assigned to. Variables only name a query if no other query has the name, as code often reuses names like
+    // `stmt`.
+    let comment_names: HashSet<String> = queries
+        .iter()
+        .filter_map(|query| statements::query_name(&query.query))
+        .map(|name| ts_type_name(&name))
+        .collect();
+    let mut variable_names: HashMap<String, usize> = HashMap::new();
+    for query in &queries {
+        if let Some(variable) = &query.variable {
+            *variable_names.entry(ts_type_name(variable)).or_default() += 1;
+        }
+    }
+    let mut named_queries = HashSet::new();
+    let mut named_types = Vec::new();
+    for ((query, statements), unsupported) in queries.iter().zip(&statements).zip(unsupported) {
+        let mut unsupported_describe = None;
+        if unsupported {
+            match Lib::Db::describe(query.describe_query.clone()).await {
+                Ok(describe) => unsupported_describe = Some(describe),
+                Err(_) => continue,
+            }
+        }
+        let mut statement_types = Vec::with_capacity(statements.len());
+        // The row type of the query, for its named types, if it is a single statement that returns rows
+        let mut row_type = None;
+        // The parameters of the statements are numbered across the query, like `?` placeholders are
+        let mut params = Vec::new();
+        for statement in statements {
+            let describe = unsupported_describe
+                .take()
+                .unwrap_or_else(|| descriptions.next().unwrap());
+            warnings.extend(column_name_warnings(&describe, &query.location));
+            let column_info = Lib::Db::column_info(statement, &describe).await.unwrap();
+            let param_count = match &describe.parameters {
+                Some(Either::Left(params)) => params.len(),
+                Some(Either::Right(count)) => *count,
+                None => 0,
+            };
+            let inferred_params: Vec<InferredParam> =
+                param_contexts(statement, Lib::Db::DIALECT, &table_columns, param_count)
+                    .iter()
+                    .map(|contexts| infer_param(contexts, &column_types))
+                    .collect();
+            params.extend(describe_to_params(
+                &lib,
+                config,
+                &describe,
+                &inferred_params,
+            ));
+
+            let return_type = ts_object_type(describe_to_column_types(
+                &lib,
+                config,
+                &describe,
+                &column_info,
+            ));
+            if statements.len() == 1 && !describe.columns.is_empty() {
+                row_type = Some(return_type.clone());
+            }
+            let mut statement_type_members = vec![("returnType".into(), return_type, false)];
+            if Lib::RAW_ROWS {
+                statement_type_members.push((
+                    "rawReturnType".into(),
+                    describe_to_raw_d_ts_type(&lib, config, &describe, &column_info),
+                    false,
+                ));
+            }
+            let kind = StatementKind {
+                command: statements::command(statement, Lib::Db::DIALECT),
+                reader: !describe.columns.is_empty(),
+            };
+            statement_type_members.extend(lib.query_type_members(
+                config,
+                &describe,
+                &column_info,
+                &kind,
+            ));
+            statement_types.push(statement_type_members);
+        }
 
-        let (return_type, _) = describe_to_d_ts_query(&lib, &describe);
-        table_types.push((table_name.into(), return_type, false));
+        let mut query_type_members = if statement_types.len() == 1 {
+            statement_types.pop().unwrap()
+        } else {
+            vec![(
+                "statements".into(),
+                ts_tuple_type(statement_types.into_iter().map(ts_object_type)),
+                false,
+            )]
+        };
+        let args = params_to_args_type(params, query.bindings.as_ref());
+        let name = match statements::query_name(&query.query) {
+            Some(name) => Some(ts_type_name(&name)),
+            None => query
+                .variable
+                .as_ref()
+                .map(|variable| ts_type_name(variable))
+                .filter(|name| variable_names[name] == 1 && !comment_names.contains(name)),
+        };
+        if let Some(name) = name {
+            if named_queries.insert(name.clone()) {
+                named_types.extend(row_type.map(|row_type| (format!("{name}Row"), row_type)));
+                if args != TS_NEVER_TYPE {
+                    named_types.push((format!("{name}Params"), args.clone()));
+                }
+            } else {
+                warnings.push(format!(
+                    "{}: another query is also named {name} in a comment, so this one gets no named types.",
+                    query.location
+                ));
+            }
+        }
+        query_type_members.push(("args".into(), args, false));
+        queries_type_members.push((
+            ts_template_literal(&query.query),
+            ts_object_type(query_type_members),
+            false,
+        ));
     }
 
+    let enums = Lib::Db::get_enums().await.unwrap();
+    let (enum_items, enums_module) = enum_declarations(&enums, config.enum_values, enums_module);
+
     let mut body = Vec::new();
     body.extend(lib.d_ts_prefix());
+    body.extend(config.type_imports().into_iter().map(type_import));
+    body.extend(enum_items);
+    body.extend(
+        [
+            ("Tables", table_types),
+            ("Insertable", insertable_types),
+            ("Updatable", updatable_types),
+        ]
+        .map(|(name, types)| {
+            ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(Box::new(TsTypeAliasDecl {
+                span: Span::default(),
+                declare: false,
+                id: Ident::new_no_ctxt(name.into(), Span::default()),
+                type_params: None,
+                type_ann: Box::new(ts_object_type(types)),
+            }))))
+        }),
+    );
     body.push(ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(Box::new(
         TsTypeAliasDecl {
             span: Span::default(),
             declare: false,
-            id: Ident::new_no_ctxt("Tables".into(), Span::default()),
+            id: Ident::new_no_ctxt("Queries".into(), Span::default()),
             type_params: None,
-            type_ann: Box::new(ts_object_type(table_types)),
+            type_ann: Box::new(ts_object_type_computed(queries_type_members)),
         },
     )))));
-    body.push(ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(Box::new(
-        TsTypeAliasDecl {
+    // The named types are exported, unless the file is a global script that declares the module of the lib,
+    // where they are global too
+    let is_module = body
+        .iter()
+        .any(|item| matches!(item, ModuleItem::ModuleDecl(_)));
+    body.extend(named_types.into_iter().map(|(name, ts_type)| {
+        let decl = Decl::TsTypeAlias(Box::new(TsTypeAliasDecl {
             span: Span::default(),
             declare: false,
-            id: Ident::new_no_ctxt("Queries".into(), Span::default()),
+            id: Ident::new_no_ctxt(name.into(), Span::default()),
             type_params: None,
-            type_ann: Box::new(ts_object_type_computed(queries_type_members)),
-        },
-    )))));
+            type_ann: Box::new(ts_type),
+        }));
+        if is_module {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                span: Span::default(),
+                decl,
+            }))
+        } else {
+            ModuleItem::Stmt(Stmt::Decl(decl))
+        }
+    }));
     body.extend(lib.d_ts_suffix());
-    Module {
+    let d_ts = Module {
         span: Span::default(),
         body,
         shebang: None,
-    }
+    };
+
+    (d_ts, enums_module, warnings)
+}
+
+fn parse_call_expr_with<Lib: SqlLib>(
+    lib: &Lib,
+    supported_lib: SupportedLib,
+    call_expr: &CallExpr,
+    location: &QueryLocation,
+) -> Option<Query> {
+    let query = lib.parse_call_expr(call_expr)?;
+    let (describe_query, bindings) = lib.rewrite_query(call_expr, &query)?;
+    Some(Query {
+        query,
+        describe_query,
+        bindings,
+        lib: supported_lib,
+        location: location.clone(),
+        variable: None,
+    })
 }
 
 impl SupportedLib {
-    pub fn parse_call_expr(&self, call_expr: &CallExpr) -> Option<Query> {
-        let query = match self {
-            SupportedLib::NodePostgres => node_postgres::NodePostgres.parse_call_expr(call_expr),
-            SupportedLib::BetterSqlite3 => better_sqlite3::BetterSqlite3.parse_call_expr(call_expr),
-            SupportedLib::MySql2 => mysql2::MySql2.parse_call_expr(call_expr),
-        };
-        query.map(|q| Query {
-            query: q,
-            lib: *self,
-        })
+    pub fn parse_call_expr(&self, call_expr: &CallExpr, location: &QueryLocation) -> Option<Query> {
+        match self {
+            SupportedLib::NodePostgres => parse_call_expr_with(
+                &node_postgres::NodePostgres::default(),
+                *self,
+                call_expr,
+                location,
+            ),
+            SupportedLib::BetterSqlite3 => parse_call_expr_with(
+                &better_sqlite3::BetterSqlite3::default(),
+                *self,
+                call_expr,
+                location,
+            ),
+            SupportedLib::MySql2 => {
+                parse_call_expr_with(&mysql2::MySql2::default(), *self, call_expr, location)
+            }
+            SupportedLib::MySql => {
+                parse_call_expr_with(&mysql::MySql::default(), *self, call_expr, location)
+            }
+            SupportedLib::Planetscale => {
+                parse_call_expr_with(&planetscale::Planetscale, *self, call_expr, location)
+            }
+            SupportedLib::ExpoSqlite => {
+                parse_call_expr_with(&expo_sqlite::ExpoSqlite, *self, call_expr, location)
+            }
+            SupportedLib::SqlJs => parse_call_expr_with(&sql_js::SqlJs, *self, call_expr, location),
+            SupportedLib::Knex(Dialect::Postgres) => parse_call_expr_with(
+                &knex::Knex::new(node_postgres::NodePostgres::default()),
+                *self,
+                call_expr,
+                location,
+            ),
+            SupportedLib::Knex(Dialect::MySql) => parse_call_expr_with(
+                &knex::Knex::new(mysql2::MySql2::default()),
+                *self,
+                call_expr,
+                location,
+            ),
+            SupportedLib::Knex(Dialect::Sqlite) => parse_call_expr_with(
+                &knex::Knex::new(better_sqlite3::BetterSqlite3::default()),
+                *self,
+                call_expr,
+                location,
+            ),
+            SupportedLib::TypeOrm(Dialect::Postgres) => parse_call_expr_with(
+                &typeorm::TypeOrm::new(node_postgres::NodePostgres::default()),
+                *self,
+                call_expr,
+                location,
+            ),
+            SupportedLib::TypeOrm(Dialect::MySql) => parse_call_expr_with(
+                &typeorm::TypeOrm::new(mysql2::MySql2::default()),
+                *self,
+                call_expr,
+                location,
+            ),
+            SupportedLib::TypeOrm(Dialect::Sqlite) => parse_call_expr_with(
+                &typeorm::TypeOrm::new(better_sqlite3::BetterSqlite3::default()),
+                *self,
+                call_expr,
+                location,
+            ),
+            SupportedLib::MikroOrm(Dialect::Postgres) => parse_call_expr_with(
+                &mikro_orm::MikroOrm::new(node_postgres::NodePostgres::default()),
+                *self,
+                call_expr,
+                location,
+            ),
+            SupportedLib::MikroOrm(Dialect::MySql) => parse_call_expr_with(
+                &mikro_orm::MikroOrm::new(mysql2::MySql2::default()),
+                *self,
+                call_expr,
+                location,
+            ),
+            SupportedLib::MikroOrm(Dialect::Sqlite) => parse_call_expr_with(
+                &mikro_orm::MikroOrm::new(better_sqlite3::BetterSqlite3::default()),
+                *self,
+                call_expr,
+                location,
+            ),
+            SupportedLib::Sequelize(Dialect::Postgres) => parse_call_expr_with(
+                &sequelize::Sequelize::new(node_postgres::NodePostgres::default()),
+                *self,
+                call_expr,
+                location,
+            ),
+            SupportedLib::Sequelize(Dialect::MySql) => parse_call_expr_with(
+                &sequelize::Sequelize::new(mysql2::MySql2::default()),
+                *self,
+                call_expr,
+                location,
+            ),
+            SupportedLib::Sequelize(Dialect::Sqlite) => parse_call_expr_with(
+                &sequelize::Sequelize::new(better_sqlite3::BetterSqlite3::default()),
+                *self,
+                call_expr,
+                location,
+            ),
+        }
     }
 
-    pub async fn create_d_ts_file(&self, queries: Vec<String>) -> Module {
+    /// Creates the d.ts file for the queries, and, if the config asks for enum values, the module with them.
+    /// Also returns warnings about the queries, like result columns that can't be told apart.
+    pub async fn create_d_ts_file(
+        &self,
+        queries: Vec<Query>,
+        config: &Config,
+    ) -> (Module, Option<Module>, Vec<String>) {
+        let enums_module = format!("./squeeel.{self}.enums");
+        let enums_module = enums_module.as_str();
         match self {
             SupportedLib::NodePostgres => {
-                create_d_ts_file(node_postgres::NodePostgres, queries).await
+                create_d_ts_file(
+                    node_postgres::NodePostgres::new(config.parsed_types.clone()),
+                    queries,
+                    config,
+                    enums_module,
+                )
+                .await
             }
             SupportedLib::BetterSqlite3 => {
-                create_d_ts_file(better_sqlite3::BetterSqlite3, queries).await
+                create_d_ts_file(
+                    better_sqlite3::BetterSqlite3::new(config.default_safe_integers),
+                    queries,
+                    config,
+                    enums_module,
+                )
+                .await
+            }
+            SupportedLib::MySql2 => {
+                create_d_ts_file(
+                    mysql2::MySql2::new(config.mysql2.clone()),
+                    queries,
+                    config,
+                    enums_module,
+                )
+                .await
+            }
+            SupportedLib::MySql => {
+                create_d_ts_file(
+                    mysql::MySql::new(config.mysql2.clone()),
+                    queries,
+                    config,
+                    enums_module,
+                )
+                .await
+            }
+            SupportedLib::Planetscale => {
+                create_d_ts_file(planetscale::Planetscale, queries, config, enums_module).await
+            }
+            SupportedLib::ExpoSqlite => {
+                create_d_ts_file(expo_sqlite::ExpoSqlite, queries, config, enums_module).await
+            }
+            SupportedLib::SqlJs => {
+                create_d_ts_file(sql_js::SqlJs, queries, config, enums_module).await
+            }
+            SupportedLib::Knex(Dialect::Postgres) => {
+                create_d_ts_file(
+                    knex::Knex::new(node_postgres::NodePostgres::new(
+                        config.parsed_types.clone(),
+                    )),
+                    queries,
+                    config,
+                    enums_module,
+                )
+                .await
+            }
+            SupportedLib::Knex(Dialect::MySql) => {
+                create_d_ts_file(
+                    knex::Knex::new(mysql2::MySql2::new(config.mysql2.clone())),
+                    queries,
+                    config,
+                    enums_module,
+                )
+                .await
+            }
+            SupportedLib::Knex(Dialect::Sqlite) => {
+                create_d_ts_file(
+                    knex::Knex::new(better_sqlite3::BetterSqlite3::default()),
+                    queries,
+                    config,
+                    enums_module,
+                )
+                .await
+            }
+            SupportedLib::TypeOrm(Dialect::Postgres) => {
+                create_d_ts_file(
+                    typeorm::TypeOrm::new(node_postgres::NodePostgres::new(
+                        config.parsed_types.clone(),
+                    )),
+                    queries,
+                    config,
+                    enums_module,
+                )
+                .await
+            }
+            SupportedLib::TypeOrm(Dialect::MySql) => {
+                create_d_ts_file(
+                    typeorm::TypeOrm::new(mysql2::MySql2::new(config.mysql2.clone())),
+                    queries,
+                    config,
+                    enums_module,
+                )
+                .await
+            }
+            SupportedLib::TypeOrm(Dialect::Sqlite) => {
+                create_d_ts_file(
+                    typeorm::TypeOrm::new(better_sqlite3::BetterSqlite3::default()),
+                    queries,
+                    config,
+                    enums_module,
+                )
+                .await
+            }
+            SupportedLib::MikroOrm(Dialect::Postgres) => {
+                create_d_ts_file(
+                    mikro_orm::MikroOrm::new(node_postgres::NodePostgres::new(
+                        config.parsed_types.clone(),
+                    )),
+                    queries,
+                    config,
+                    enums_module,
+                )
+                .await
+            }
+            SupportedLib::MikroOrm(Dialect::MySql) => {
+                create_d_ts_file(
+                    mikro_orm::MikroOrm::new(mysql2::MySql2::new(config.mysql2.clone())),
+                    queries,
+                    config,
+                    enums_module,
+                )
+                .await
+            }
+            SupportedLib::MikroOrm(Dialect::Sqlite) => {
+                create_d_ts_file(
+                    mikro_orm::MikroOrm::new(better_sqlite3::BetterSqlite3::default()),
+                    queries,
+                    config,
+                    enums_module,
+                )
+                .await
+            }
+            SupportedLib::Sequelize(Dialect::Postgres) => {
+                create_d_ts_file(
+                    sequelize::Sequelize::new(node_postgres::NodePostgres::new(
+                        config.parsed_types.clone(),
+                    )),
+                    queries,
+                    config,
+                    enums_module,
+                )
+                .await
+            }
+            SupportedLib::Sequelize(Dialect::MySql) => {
+                create_d_ts_file(
+                    sequelize::Sequelize::new(mysql2::MySql2::new(config.mysql2.clone())),
+                    queries,
+                    config,
+                    enums_module,
+                )
+                .await
+            }
+            SupportedLib::Sequelize(Dialect::Sqlite) => {
+                create_d_ts_file(
+                    sequelize::Sequelize::new(better_sqlite3::BetterSqlite3::default()),
+                    queries,
+                    config,
+                    enums_module,
+                )
+                .await
             }
-            SupportedLib::MySql2 => create_d_ts_file(mysql2::MySql2, queries).await,
         }
     }
 }
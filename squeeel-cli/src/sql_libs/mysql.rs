@@ -0,0 +1,156 @@
+use crate::config::{Config, MySql2Options};
+use crate::describe::ColumnInfo;
+use crate::sql_libs::mysql2::{mysql_declared_type_to_ts_type, mysql_input_ts_type};
+use crate::sql_libs::{SqlLib, StatementKind};
+use crate::utils::ast::query_string;
+use crate::utils::constants::GEOMETRY_TYPE;
+use crate::utils::ts_types::{
+    TS_NULL_TYPE, TS_NUMBER_TYPE, TS_STRING_TYPE, TS_UNKNOWN_TYPE, ts_type_ref, ts_union_type,
+};
+use sqlx_core::describe::Describe;
+use sqlx_core::type_info::TypeInfo;
+use swc_common::BytePos;
+use swc_ecma_ast::{Expr, ModuleItem, TsType};
+use swc_ecma_parser::{Lexer, Parser, StringInput, Syntax, TsSyntax};
+
+/// The original `mysql` package, not to be confused with `mysql2`
+#[derive(Default)]
+pub struct MySql {
+    options: MySql2Options,
+}
+
+impl MySql {
+    /// mysql shares the `supportBigNumbers`, `bigNumberStrings` and `dateStrings` options with mysql2
+    pub fn new(options: MySql2Options) -> Self {
+        Self { options }
+    }
+}
+
+impl SqlLib for MySql {
+    type Db = sqlx::MySql;
+
+    fn parse_call_expr(&self, call_expr: &swc_ecma_ast::CallExpr) -> Option<String> {
+        let swc_ecma_ast::Callee::Expr(expr) = &call_expr.callee else {
+            return None;
+        };
+
+        let swc_ecma_ast::Expr::Member(member_expr) = &**expr else {
+            return None;
+        };
+
+        let obj = &member_expr.obj.as_ident()?.sym;
+        let prop = &member_expr.prop.as_ident()?.sym;
+        if (obj != "connection" && obj != "pool") || prop != "query" {
+            return None;
+        }
+
+        // `connection.query(sql, values, callback)`, where both the values and the callback are optional
+        let mut args_iter = call_expr.args.iter();
+        let query_expr = args_iter.next()?;
+        let _values = args_iter.next();
+        let _callback = args_iter.next();
+        if args_iter.next().is_some() {
+            return None;
+        }
+
+        if query_expr.spread.is_some() {
+            return None;
+        }
+
+        let query = query_string(&query_expr.expr)?;
+
+        Some(query)
+    }
+
+    fn db_type_to_ts_type(&self, ty: &<Self::Db as sqlx::Database>::TypeInfo) -> TsType {
+        // Unlike mysql2, JSON is not parsed, and `DECIMAL` is decoded like `BIGINT`, as a number unless
+        // `supportBigNumbers` is set
+        let options = &self.options;
+        match ty.name().to_lowercase().as_str() {
+            "char" | "varchar" | "tinytext" | "text" | "mediumtext" | "longtext" | "time"
+            | "enum" | "set" | "json" => TS_STRING_TYPE,
+            "boolean" | "tinyint" | "tinyint unsigned" | "smallint" | "smallint unsigned"
+            | "mediumint" | "mediumint unsigned" | "int" | "int unsigned" | "float" | "double"
+            | "year" => TS_NUMBER_TYPE,
+            "bigint" | "bigint unsigned" | "decimal" => match (
+                options.support_big_numbers == Some(true),
+                options.big_number_strings == Some(true),
+            ) {
+                (false, _) => TS_NUMBER_TYPE,
+                (true, false) => ts_union_type([TS_NUMBER_TYPE, TS_STRING_TYPE]),
+                (true, true) => TS_STRING_TYPE,
+            },
+            "null" => TS_NULL_TYPE,
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" | "bit" => {
+                ts_type_ref("Buffer")
+            }
+            date @ ("date" | "datetime" | "timestamp") if options.date_string(date) => {
+                TS_STRING_TYPE
+            }
+            "date" | "datetime" | "timestamp" => ts_type_ref("Date"),
+            "geometry" => ts_type_ref("Geometry"),
+            _ => TS_UNKNOWN_TYPE,
+        }
+    }
+
+    fn declared_type_to_ts_type(&self, declared_type: &str, _strict: bool) -> Option<TsType> {
+        mysql_declared_type_to_ts_type(declared_type, true)
+    }
+
+    fn db_type_to_input_ts_type(&self, ty: &<Self::Db as sqlx::Database>::TypeInfo) -> TsType {
+        mysql_input_ts_type(ty).unwrap_or_else(|| self.db_type_to_ts_type(ty))
+    }
+
+    // Statements that return no rows give an `OkPacket` instead
+    fn query_type_members(
+        &self,
+        _config: &Config,
+        _describe: &Describe<sqlx::MySql>,
+        _column_info: &[ColumnInfo],
+        kind: &StatementKind,
+    ) -> Vec<(Expr, TsType, bool)> {
+        vec![("reader".into(), kind.reader_ts_type(), false)]
+    }
+
+    fn d_ts_prefix(&self) -> Vec<ModuleItem> {
+        let prefix = format!(
+            r#"import type mysql from "mysql";
+{GEOMETRY_TYPE}
+"#
+        );
+        let lexer = Lexer::new(
+            Syntax::Typescript(TsSyntax {
+                ..Default::default()
+            }),
+            Default::default(),
+            StringInput::new(&prefix, BytePos(0), BytePos(prefix.len() as u32)),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+        parser.parse_typescript_module().unwrap().body
+    }
+
+    fn d_ts_suffix(&self) -> Vec<ModuleItem> {
+        let suffix = r#"declare module "mysql" {
+    export interface QueryFunction {
+        <T extends keyof Queries>(
+            ...params: Queries[T]["args"] extends never ?
+                [sql: T, callback?: (err: mysql.MysqlError | null, results?: Queries[T]["reader"] extends true ? Queries[T]["returnType"][] : mysql.OkPacket, fields?: mysql.FieldInfo[]) => void] :
+                [sql: T, values: Queries[T]["args"], callback?: (err: mysql.MysqlError | null, results?: Queries[T]["reader"] extends true ? Queries[T]["returnType"][] : mysql.OkPacket, fields?: mysql.FieldInfo[]) => void]
+        ): mysql.Query;
+    }
+}
+"#;
+        let lexer = Lexer::new(
+            Syntax::Typescript(TsSyntax {
+                no_early_errors: true,
+                ..Default::default()
+            }),
+            Default::default(),
+            StringInput::new(suffix, BytePos(0), BytePos(suffix.len() as u32)),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+        parser.parse_typescript_module().unwrap().body
+    }
+}
@@ -1,17 +1,147 @@
-use crate::sql_libs::SqlLib;
-use crate::utils::constants::JSON_VALUE_TYPE;
+use crate::config::Config;
+use crate::config::{DateStrings, MySql2Options};
+use crate::describe::ColumnInfo;
+use crate::sql_libs::{SqlLib, StatementKind};
+use crate::utils::ast::{find_config_object, object_bool_prop, query_string};
+use crate::utils::constants::{GEOMETRY_TYPE, JSON_VALUE_TYPE};
 use crate::utils::ts_types::{
-    TS_NULL_TYPE, TS_NUMBER_TYPE, TS_STRING_TYPE, TS_UNKNOWN_TYPE, ts_type_ref,
+    TS_BIGINT_TYPE, TS_BOOLEAN_TYPE, TS_NULL_TYPE, TS_NUMBER_TYPE, TS_STRING_TYPE, TS_UNKNOWN_TYPE,
+    ts_array_type, ts_string_literal_type, ts_type_ref, ts_union_type,
 };
+use sqlx_core::describe::Describe;
 use sqlx_core::type_info::TypeInfo;
 use swc_common::BytePos;
-use swc_ecma_ast::{ModuleItem, TsType};
+use swc_ecma_ast::{Expr, Module, ModuleItem, TsType};
 use swc_ecma_parser::{Lexer, Parser, StringInput, Syntax, TsSyntax};
 
-pub struct MySql2;
+#[derive(Default)]
+pub struct MySql2 {
+    options: MySql2Options,
+}
+
+impl MySql2 {
+    pub fn new(options: MySql2Options) -> Self {
+        Self { options }
+    }
+}
+
+/// The decoding options in the object literal passed to `createConnection` or `createPool`
+pub fn detect_mysql2_options(module: &Module) -> Option<MySql2Options> {
+    let options = find_config_object(module, &["createConnection", "createPool"])?;
+    Some(MySql2Options {
+        support_big_numbers: object_bool_prop(Some(&options), "supportBigNumbers"),
+        big_number_strings: object_bool_prop(Some(&options), "bigNumberStrings"),
+        date_strings: object_bool_prop(Some(&options), "dateStrings").map(DateStrings::All),
+        decimal_numbers: object_bool_prop(Some(&options), "decimalNumbers"),
+        multiple_statements: object_bool_prop(Some(&options), "multipleStatements"),
+        tiny_int_as_boolean: None,
+    })
+}
+
+/// SET values with more members than this are typed as `string`, instead of a union of every combination
+const MAX_EXPANDED_SET_VALUES: usize = 5;
+
+/// The values in the declared type of an `ENUM` or `SET` column, like `enum('a','b')`, where quotes are
+/// escaped by doubling them
+fn mysql_declared_values(declared_type: &str) -> Option<Vec<String>> {
+    let mut chars = declared_type.chars().peekable();
+    let mut values = Vec::new();
+    loop {
+        if chars.next()? != '\'' {
+            return None;
+        }
+        let mut value = String::new();
+        loop {
+            match chars.next()? {
+                '\'' if chars.peek() == Some(&'\'') => {
+                    chars.next();
+                    value.push('\'');
+                }
+                '\'' => break,
+                c => value.push(c),
+            }
+        }
+        values.push(value);
+        match chars.next()? {
+            ',' => continue,
+            ')' => return Some(values),
+            _ => return None,
+        }
+    }
+}
+
+/// The TS type of a parameter of a MySQL type, for the types that mysql and mysql2 accept more values for
+/// than they return. Both send dates and buffers as such, and anything else as its string representation.
+pub(super) fn mysql_input_ts_type(
+    ty: &<sqlx::MySql as sqlx::Database>::TypeInfo,
+) -> Option<TsType> {
+    match ty.name().to_lowercase().as_str() {
+        "boolean" => Some(ts_union_type([TS_BOOLEAN_TYPE, TS_NUMBER_TYPE])),
+        "bigint" | "bigint unsigned" | "decimal" => {
+            Some(ts_union_type([TS_NUMBER_TYPE, TS_STRING_TYPE]))
+        }
+        "date" | "datetime" | "timestamp" => {
+            Some(ts_union_type([ts_type_ref("Date"), TS_STRING_TYPE]))
+        }
+        "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" | "bit" => {
+            Some(ts_union_type([ts_type_ref("Buffer"), TS_STRING_TYPE]))
+        }
+        _ => None,
+    }
+}
+
+/// The TS type of a column from its declared type, for the types where that tells more than the described
+/// type: `ENUM` and `SET` columns, whose values are strings, and, if the driver decodes them, spatial columns.
+/// SET values are the members in the order they are declared, joined by commas.
+pub(super) fn mysql_declared_type_to_ts_type(declared_type: &str, spatial: bool) -> Option<TsType> {
+    let declared_type = declared_type.trim();
+    let lowercase = declared_type.to_lowercase();
+    if lowercase.starts_with("enum(") {
+        let values = mysql_declared_values(&declared_type["enum(".len()..])?;
+        return Some(ts_union_type(
+            values.into_iter().map(ts_string_literal_type),
+        ));
+    }
+    if lowercase.starts_with("set(") {
+        let values = mysql_declared_values(&declared_type["set(".len()..])?;
+        if values.len() > MAX_EXPANDED_SET_VALUES {
+            return Some(TS_STRING_TYPE);
+        }
+        let combinations = (0..1usize << values.len()).map(|members| {
+            let value = values
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| members & (1 << i) != 0)
+                .map(|(_, value)| value.as_str())
+                .collect::<Vec<_>>()
+                .join(",");
+            ts_string_literal_type(value)
+        });
+        return Some(ts_union_type(combinations));
+    }
+    if !spatial {
+        return None;
+    }
+    let point = || ts_type_ref("Point");
+    match lowercase.as_str() {
+        "point" => Some(point()),
+        "linestring" | "multipoint" => Some(ts_array_type(point())),
+        "polygon" | "multilinestring" => Some(ts_array_type(ts_array_type(point()))),
+        "multipolygon" => Some(ts_array_type(ts_array_type(ts_array_type(point())))),
+        "geometrycollection" | "geomcollection" => Some(ts_array_type(ts_type_ref("Geometry"))),
+        "geometry" => Some(ts_type_ref("Geometry")),
+        _ => None,
+    }
+}
 
 impl SqlLib for MySql2 {
     type Db = sqlx::MySql;
+    const MULTIPLE_STATEMENTS: bool = true;
+
+    fn multiple_statements_unsupported(&self, _sql: &str) -> Option<&'static str> {
+        (self.options.multiple_statements != Some(true))
+            .then_some("mysql2 only runs several statements with `multipleStatements: true`")
+    }
 
     fn parse_call_expr(&self, call_expr: &swc_ecma_ast::CallExpr) -> Option<String> {
         let swc_ecma_ast::Callee::Expr(expr) = &call_expr.callee else {
@@ -39,37 +169,75 @@ impl SqlLib for MySql2 {
             return None;
         }
 
-        let query = match &*query_expr.expr {
-            swc_ecma_ast::Expr::Lit(lit) => lit.as_str()?.value.to_string(),
-            swc_ecma_ast::Expr::Tpl(tpl) => tpl
-                .quasis
-                .iter()
-                .map(|quasi| quasi.raw.to_string())
-                .collect::<Vec<_>>()
-                .join(""),
-            _ => return None,
-        };
+        let query = query_string(&query_expr.expr)?;
 
         Some(query)
     }
 
     fn db_type_to_ts_type(&self, ty: &<Self::Db as sqlx::Database>::TypeInfo) -> TsType {
+        let options = &self.options;
         match ty.name().to_lowercase().as_str() {
-            "text" | "char" | "varchar" | "decimal" | "time" => TS_STRING_TYPE,
-            "boolean" | "tinyint" | "smallint" | "mediumint" | "int" | "bigint" | "float"
-            | "double" | "bigint unsigned" => TS_NUMBER_TYPE,
+            "char" | "varchar" | "tinytext" | "text" | "mediumtext" | "longtext" | "time"
+            | "enum" | "set" => TS_STRING_TYPE,
+            // `tinyint(1)`
+            "boolean" if options.tiny_int_as_boolean == Some(true) => TS_BOOLEAN_TYPE,
+            "boolean" | "tinyint" | "tinyint unsigned" | "smallint" | "smallint unsigned"
+            | "mediumint" | "mediumint unsigned" | "int" | "int unsigned" | "float" | "double"
+            | "year" => TS_NUMBER_TYPE,
+            "bigint" | "bigint unsigned" => match (
+                options.support_big_numbers == Some(true),
+                options.big_number_strings == Some(true),
+            ) {
+                (false, _) => TS_NUMBER_TYPE,
+                (true, false) => ts_union_type([TS_NUMBER_TYPE, TS_STRING_TYPE]),
+                (true, true) => TS_STRING_TYPE,
+            },
+            "decimal" if options.decimal_numbers == Some(true) => TS_NUMBER_TYPE,
+            "decimal" => TS_STRING_TYPE,
             "null" => TS_NULL_TYPE,
-            "binary" | "varbinary" | "blob" => ts_type_ref("Buffer"),
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" | "bit" => {
+                ts_type_ref("Buffer")
+            }
+            date @ ("date" | "datetime" | "timestamp") if options.date_string(date) => {
+                TS_STRING_TYPE
+            }
             "date" | "datetime" | "timestamp" => ts_type_ref("Date"),
             "json" => ts_type_ref("JsonValue"),
+            "geometry" => ts_type_ref("Geometry"),
             _ => TS_UNKNOWN_TYPE,
         }
     }
 
+    fn declared_type_to_ts_type(&self, declared_type: &str, _strict: bool) -> Option<TsType> {
+        mysql_declared_type_to_ts_type(declared_type, true)
+    }
+
+    fn db_type_to_input_ts_type(&self, ty: &<Self::Db as sqlx::Database>::TypeInfo) -> TsType {
+        // The decoding options only apply to returned values
+        match ty.name().to_lowercase().as_str() {
+            "bigint" | "bigint unsigned" => {
+                ts_union_type([TS_NUMBER_TYPE, TS_BIGINT_TYPE, TS_STRING_TYPE])
+            }
+            _ => mysql_input_ts_type(ty).unwrap_or_else(|| self.db_type_to_ts_type(ty)),
+        }
+    }
+
+    // Statements that return no rows resolve to a `ResultSetHeader` instead
+    fn query_type_members(
+        &self,
+        _config: &Config,
+        _describe: &Describe<sqlx::MySql>,
+        _column_info: &[ColumnInfo],
+        kind: &StatementKind,
+    ) -> Vec<(Expr, TsType, bool)> {
+        vec![("reader".into(), kind.reader_ts_type(), false)]
+    }
+
     fn d_ts_prefix(&self) -> Vec<ModuleItem> {
         let prefix = format!(
             r#"import type mysql from "mysql2/promise";
 {JSON_VALUE_TYPE}
+{GEOMETRY_TYPE}
         "#
         );
         let lexer = Lexer::new(
@@ -85,7 +253,14 @@ impl SqlLib for MySql2 {
     }
 
     fn d_ts_suffix(&self) -> Vec<ModuleItem> {
-        let suffix = r#"declare module "mysql2/promise" {
+        let suffix = r#"type TypedStatementResult<S> = S extends { reader: true; returnType: infer Row } ? Row[] : mysql.ResultSetHeader;
+// Queries with several statements, with `multipleStatements: true`, give a result for each statement
+type TypedQueryResult<T extends string> = T extends keyof Queries
+    ? Queries[T] extends { statements: infer Statements }
+        ? { [I in keyof Statements]: TypedStatementResult<Statements[I]> }
+        : TypedStatementResult<Queries[T]>
+    : unknown;
+declare module "mysql2/promise" {
     export interface Connection {
         query<T extends string>(
 			...params: T extends keyof Queries ? 
@@ -93,7 +268,7 @@ impl SqlLib for MySql2 {
 					[sql: T] : 
 					[sql: T, values: Queries[T]["args"]] : 
 				[sql: T, values: any]
-		): Promise<[T extends keyof Queries ? Queries[T]["returnType"][] : unknown, mysql.FieldPacket[]]>;
+		): Promise<[TypedQueryResult<T>, mysql.FieldPacket[]]>;
     }
 }
 "#;
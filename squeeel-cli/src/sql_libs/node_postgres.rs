@@ -1,17 +1,62 @@
-use crate::sql_libs::SqlLib;
+use crate::config::Config;
+use crate::describe::ColumnInfo;
+use crate::sql_libs::{SqlLib, StatementKind};
+use crate::utils::ast::{object_prop, query_string};
 use crate::utils::constants::JSON_VALUE_TYPE;
+use crate::utils::sql_placeholders::{Placeholder, placeholders};
 use crate::utils::ts_types::{
-    TS_BOOLEAN_TYPE, TS_NUMBER_TYPE, TS_STRING_TYPE, TS_UNKNOWN_TYPE, ts_object_type, ts_type_ref,
+    TS_BIGINT_TYPE, TS_BOOLEAN_TYPE, TS_NUMBER_TYPE, TS_STRING_TYPE, TS_UNKNOWN_TYPE,
+    ts_array_type, ts_nullable_type, ts_object_type, ts_type_name, ts_type_ref, ts_union_type,
 };
+use sqlx_core::describe::Describe;
 use sqlx_core::type_info::TypeInfo;
 use swc_common::BytePos;
-use swc_ecma_ast::{ModuleItem, TsLit, TsLitType, TsType, TsUnionOrIntersectionType, TsUnionType};
+use swc_ecma_ast::{Expr, ModuleItem, TsType};
 use swc_ecma_parser::{Lexer, Parser, StringInput, Syntax, TsSyntax};
 
-pub struct NodePostgres;
+#[derive(Default)]
+pub struct NodePostgres {
+    parsed_types: Vec<String>,
+}
+
+impl NodePostgres {
+    /// The types with a parser are typed as what the parser is expected to return, rather than as text
+    pub fn new(parsed_types: Vec<String>) -> Self {
+        Self { parsed_types }
+    }
+
+    /// Whether a parser is registered for the type, by its name or OID
+    fn is_parsed(&self, ty: &<<Self as SqlLib>::Db as sqlx::Database>::TypeInfo) -> bool {
+        let oid = ty.oid().map(|oid| oid.0.to_string());
+        self.parsed_types
+            .iter()
+            .any(|parsed| parsed.eq_ignore_ascii_case(ty.name()) || Some(parsed) == oid.as_ref())
+    }
+
+    /// A composite type as an object of its fields, which can all be null
+    fn composite_ts_type(&self, fields: &[(String, sqlx_postgres::PgTypeInfo)]) -> TsType {
+        ts_object_type(fields.iter().map(|(name, ty)| {
+            (
+                name.as_str().into(),
+                ts_nullable_type(self.db_type_to_ts_type(ty)),
+                false,
+            )
+        }))
+    }
+}
 
 impl SqlLib for NodePostgres {
     type Db = sqlx::Postgres;
+    const RAW_ROWS: bool = true;
+    const MULTIPLE_STATEMENTS: bool = true;
+
+    // Queries with parameters are run with the extended protocol, which only takes one statement
+    fn multiple_statements_unsupported(&self, sql: &str) -> Option<&'static str> {
+        placeholders(sql)
+            .iter()
+            .any(|(_, placeholder)| matches!(placeholder, Placeholder::Dollar(_)))
+            .then_some("node-postgres only runs several statements in queries without parameters")
+    }
 
     fn parse_call_expr(&self, call_expr: &swc_ecma_ast::CallExpr) -> Option<String> {
         let swc_ecma_ast::Callee::Expr(expr) = &call_expr.callee else {
@@ -39,29 +84,56 @@ impl SqlLib for NodePostgres {
             return None;
         }
 
-        let query = match &*query_expr.expr {
-            swc_ecma_ast::Expr::Lit(lit) => lit.as_str()?.value.to_string(),
-            swc_ecma_ast::Expr::Tpl(tpl) => tpl
-                .quasis
-                .iter()
-                .map(|quasi| quasi.raw.to_string())
-                .collect::<Vec<_>>()
-                .join(""),
-            _ => return None,
-        };
+        // The query can also be passed as the `text` of a query config, like
+        // `{ text: "...", rowMode: "array" }`
+        let query_expr = object_prop(&query_expr.expr, "text").unwrap_or(&query_expr.expr);
+        let query = query_string(query_expr)?;
 
         Some(query)
     }
 
     fn db_type_to_ts_type(&self, ty: &<Self::Db as sqlx::Database>::TypeInfo) -> TsType {
+        match ty.kind() {
+            // Only arrays of built-in types are parsed by default. Arrays of user-defined types have their own
+            // OID, without a parser, so they are returned in their text representation, like `{a,b}`.
+            sqlx_postgres::PgTypeKind::Array(elem) => {
+                // The parser of an array is expected to parse the elements too
+                let parsed = self.is_parsed(ty);
+                return match elem.kind() {
+                    sqlx_postgres::PgTypeKind::Composite(fields) if parsed => {
+                        ts_array_type(self.composite_ts_type(fields))
+                    }
+                    sqlx_postgres::PgTypeKind::Enum(_)
+                    | sqlx_postgres::PgTypeKind::Composite(_)
+                    | sqlx_postgres::PgTypeKind::Domain(_)
+                        if !parsed =>
+                    {
+                        TS_STRING_TYPE
+                    }
+                    _ => ts_array_type(self.db_type_to_ts_type(elem)),
+                };
+            }
+            // A domain is returned as its base type, e.g. `text` for an `email` domain
+            sqlx_postgres::PgTypeKind::Domain(base) => return self.db_type_to_ts_type(base),
+            sqlx_postgres::PgTypeKind::Composite(fields) if self.is_parsed(ty) => {
+                return self.composite_ts_type(fields);
+            }
+            // There are no parsers for ranges and composite types, so they are returned in their text
+            // representation, like `(1,foo)`
+            sqlx_postgres::PgTypeKind::Range(_) | sqlx_postgres::PgTypeKind::Composite(_) => {
+                return TS_STRING_TYPE;
+            }
+            _ => {}
+        }
+
         match ty.name().to_lowercase().as_str() {
             "bool" => TS_BOOLEAN_TYPE,
             "line" | "polygon" | "path" | "lseg" | "jsonpath" | "tsrange" | "int4range"
             | "numrange" | "int8range" | "tstzrange" | "daterange" | "box" | "uuid" | "varbit"
             | "bit" | "numeric" | "text" | "varchar" | "bpchar" | "cidr" | "inet" | "int8"
-            | "time" | "timetz" | "money" | "name" | "char" | "macaddr" | "macaddr8" => {
-                TS_STRING_TYPE
-            }
+            | "time" | "timetz" | "money" | "name" | "char" | "macaddr" | "macaddr8" | "citext"
+            | "int4multirange" | "int8multirange" | "nummultirange" | "tsmultirange"
+            | "tstzmultirange" | "datemultirange" => TS_STRING_TYPE,
             "float4" | "float8" | "int2" | "int4" | "oid" => TS_NUMBER_TYPE,
             "timestamp" | "timestamptz" | "date" => ts_type_ref("Date"),
             "point" => ts_object_type([
@@ -85,27 +157,50 @@ impl SqlLib for NodePostgres {
                 ("radius".into(), TS_NUMBER_TYPE, false),
             ]),
             _ => {
-                if let sqlx_postgres::PgTypeKind::Enum(enm) = ty.kind() {
-                    return TsType::TsUnionOrIntersectionType(
-                        TsUnionOrIntersectionType::TsUnionType(TsUnionType {
-                            span: Default::default(),
-                            types: enm
-                                .iter()
-                                .map(|case| {
-                                    Box::new(TsType::TsLitType(TsLitType {
-                                        span: Default::default(),
-                                        lit: TsLit::Str(case.to_string().into()),
-                                    }))
-                                })
-                                .collect(),
-                        }),
-                    );
+                // Enums are declared once as a named type alias
+                if let sqlx_postgres::PgTypeKind::Enum(_) = ty.kind() {
+                    return ts_type_ref(ts_type_name(ty.name()));
                 };
                 TS_UNKNOWN_TYPE
             }
         }
     }
 
+    fn db_type_to_input_ts_type(&self, ty: &<Self::Db as sqlx::Database>::TypeInfo) -> TsType {
+        match ty.kind() {
+            sqlx_postgres::PgTypeKind::Array(elem) => {
+                return ts_array_type(self.db_type_to_input_ts_type(elem));
+            }
+            sqlx_postgres::PgTypeKind::Domain(base) => return self.db_type_to_input_ts_type(base),
+            // Objects are sent as JSON, which is not the text representation of a composite value
+            sqlx_postgres::PgTypeKind::Composite(_) => return TS_STRING_TYPE,
+            _ => {}
+        }
+
+        // Dates and buffers are sent as such, and anything else as its string representation
+        match ty.name().to_lowercase().as_str() {
+            "int8" => ts_union_type([TS_NUMBER_TYPE, TS_BIGINT_TYPE, TS_STRING_TYPE]),
+            "numeric" => ts_union_type([TS_NUMBER_TYPE, TS_STRING_TYPE]),
+            "timestamp" | "timestamptz" | "date" => {
+                ts_union_type([ts_type_ref("Date"), TS_STRING_TYPE])
+            }
+            "bytea" => ts_union_type([ts_type_ref("Buffer"), TS_STRING_TYPE]),
+            "point" | "circle" | "interval" => TS_STRING_TYPE,
+            _ => self.db_type_to_ts_type(ty),
+        }
+    }
+
+    // `QueryResult.command` is typed by the statement
+    fn query_type_members(
+        &self,
+        _config: &Config,
+        _describe: &Describe<sqlx::Postgres>,
+        _column_info: &[ColumnInfo],
+        kind: &StatementKind,
+    ) -> Vec<(Expr, TsType, bool)> {
+        vec![("command".into(), kind.command_ts_type(), false)]
+    }
+
     fn d_ts_prefix(&self) -> Vec<ModuleItem> {
         let prefix = format!(
             r#"import type pg from "pg";
@@ -125,14 +220,31 @@ impl SqlLib for NodePostgres {
     }
 
     fn d_ts_suffix(&self) -> Vec<ModuleItem> {
-        let suffix = r#"declare module "pg" {
+        let suffix = r#"// Inside the module declaration, `QueryResult` would be the one of pg
+type TypedStatementResult<S, ArrayRows extends boolean> = S extends {
+    returnType: infer Row extends pg.QueryResultRow;
+    rawReturnType: infer RawRow extends unknown[];
+    command: infer Command;
+}
+    ? (ArrayRows extends true ? pg.QueryArrayResult<RawRow> : pg.QueryResult<Row>) & { command: Command }
+    : never;
+// Queries with several statements give a result for each statement
+type TypedQueryResult<T extends string, ArrayRows extends boolean = false> = T extends keyof Queries
+    ? Queries[T] extends { statements: infer Statements }
+        ? { [I in keyof Statements]: TypedStatementResult<Statements[I], ArrayRows> }
+        : TypedStatementResult<Queries[T], ArrayRows>
+    : ArrayRows extends true ? pg.QueryArrayResult : pg.QueryResult<unknown>;
+type TypedQueryConfig<T extends string> = T extends keyof Queries
+    ? Queries[T]["args"] extends never ? { text: T; values?: [] } : { text: T; values: Queries[T]["args"] }
+    : { text: T; values?: any };
+declare module "pg" {
     export interface ClientBase {
         query<T extends string>(
             ...params: T extends keyof Queries ? 
                 Queries[T]["args"] extends never ? 
-                    [q: T, callback: (err: Error, result: pg.QueryResult<T extends keyof Queries ? Queries[T]["returnType"] : unknown>) => void,] : 
-                    [q: T, args: Queries[T]["args"], callback: (err: Error, result: pg.QueryResult<T extends keyof Queries ? Queries[T]["returnType"] : unknown>) => void,] 
-                : [q: T, args: any, callback: (err: Error, result:pg.QueryResult<T extends keyof Queries ? Queries[T]["returnType"] : unknown>) => void,]
+                    [q: T, callback: (err: Error, result: TypedQueryResult<T>) => void,] : 
+                    [q: T, args: Queries[T]["args"], callback: (err: Error, result: TypedQueryResult<T>) => void,] 
+                : [q: T, args: any, callback: (err: Error, result: TypedQueryResult<T>) => void,]
         ): void;
         query<T extends string>(
             ...params: T extends keyof Queries ? 
@@ -140,7 +252,10 @@ impl SqlLib for NodePostgres {
                     [q: T] : 
                     [q: T, args: Queries[T]["args"]] 
                 : [q: T, args: any]
-        ): Promise<pg.QueryResult<T extends keyof Queries ? Queries[T]["returnType"] : unknown>>;
+        ): Promise<TypedQueryResult<T>>;
+        // With `rowMode: "array"`, rows are arrays of the values in column order
+        query<T extends string>(config: TypedQueryConfig<T> & { rowMode: "array" }): Promise<TypedQueryResult<T, true>>;
+        query<T extends string>(config: TypedQueryConfig<T> & { rowMode?: undefined }): Promise<TypedQueryResult<T>>;
     }
 }
 "#;
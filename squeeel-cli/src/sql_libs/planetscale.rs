@@ -0,0 +1,168 @@
+use crate::sql_libs::mysql2::mysql_declared_type_to_ts_type;
+use crate::sql_libs::{Binding, Bindings, SqlLib};
+use crate::utils::ast::query_string;
+use crate::utils::constants::JSON_VALUE_TYPE;
+use crate::utils::sql_placeholders::{Placeholder, placeholders, replace_placeholders};
+use crate::utils::ts_types::{
+    TS_BOOLEAN_TYPE, TS_NULL_TYPE, TS_NUMBER_TYPE, TS_STRING_TYPE, TS_UNKNOWN_TYPE, ts_type_ref,
+    ts_union_type,
+};
+use sqlx_core::type_info::TypeInfo;
+use swc_common::BytePos;
+use swc_ecma_ast::{CallExpr, Expr, ModuleItem, TsType};
+use swc_ecma_parser::{Lexer, Parser, StringInput, Syntax, TsSyntax};
+
+/// The `@planetscale/database` serverless driver
+pub struct Planetscale;
+
+impl SqlLib for Planetscale {
+    type Db = sqlx::MySql;
+
+    fn parse_call_expr(&self, call_expr: &CallExpr) -> Option<String> {
+        let swc_ecma_ast::Callee::Expr(expr) = &call_expr.callee else {
+            return None;
+        };
+
+        let swc_ecma_ast::Expr::Member(member_expr) = &**expr else {
+            return None;
+        };
+
+        let obj = &member_expr.obj.as_ident()?.sym;
+        let prop = &member_expr.prop.as_ident()?.sym;
+        if !["conn", "connection", "client", "tx"].contains(&&**obj) || prop != "execute" {
+            return None;
+        }
+
+        // A third options argument can ask for the rows as arrays, which we don't type
+        let mut args_iter = call_expr.args.iter();
+        let query_expr = args_iter.next()?;
+        let _args = args_iter.next();
+        if args_iter.next().is_some() {
+            return None;
+        }
+
+        if query_expr.spread.is_some() {
+            return None;
+        }
+
+        let query = query_string(&query_expr.expr)?;
+
+        Some(query)
+    }
+
+    fn rewrite_query(
+        &self,
+        call_expr: &CallExpr,
+        query: &str,
+    ) -> Option<(String, Option<Bindings>)> {
+        // The driver substitutes `:name` placeholders itself when the args are an object
+        let named = match call_expr.args.get(1).map(|arg| &*arg.expr) {
+            Some(Expr::Object(_)) => true,
+            Some(Expr::Array(_)) | None => false,
+            Some(_) => placeholders(query)
+                .iter()
+                .any(|(_, placeholder)| matches!(placeholder, Placeholder::Named(_))),
+        };
+        if !named {
+            return Some((query.to_string(), None));
+        }
+
+        let mut bindings = Vec::new();
+        let sql = replace_placeholders(query, |placeholder, original| match placeholder {
+            Placeholder::Named(name) => {
+                bindings.push((name.to_string(), Binding::Parameter(bindings.len())));
+                Some("?".to_string())
+            }
+            _ => Some(original.to_string()),
+        })?;
+
+        Some((sql, Some(Bindings::Named(bindings))))
+    }
+
+    fn db_type_to_ts_type(&self, ty: &<Self::Db as sqlx::Database>::TypeInfo) -> TsType {
+        // The driver only casts small numbers, so 64 bit integers and dates stay strings
+        match ty.name().to_lowercase().as_str() {
+            "char" | "varchar" | "tinytext" | "text" | "mediumtext" | "longtext" | "decimal"
+            | "time" | "enum" | "set" | "bigint" | "bigint unsigned" | "date" | "datetime"
+            | "timestamp" => TS_STRING_TYPE,
+            "boolean" | "tinyint" | "tinyint unsigned" | "smallint" | "smallint unsigned"
+            | "mediumint" | "mediumint unsigned" | "int" | "int unsigned" | "float" | "double"
+            | "year" => TS_NUMBER_TYPE,
+            "null" => TS_NULL_TYPE,
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" | "bit"
+            | "geometry" => ts_type_ref("Uint8Array"),
+            "json" => ts_type_ref("JsonValue"),
+            _ => TS_UNKNOWN_TYPE,
+        }
+    }
+
+    fn declared_type_to_ts_type(&self, declared_type: &str, _strict: bool) -> Option<TsType> {
+        // Spatial values are returned as they are sent, in the WKB format
+        mysql_declared_type_to_ts_type(declared_type, false)
+    }
+
+    fn db_type_to_input_ts_type(&self, ty: &<Self::Db as sqlx::Database>::TypeInfo) -> TsType {
+        // Values are formatted into the query, where numbers can stand for the types returned as strings
+        match ty.name().to_lowercase().as_str() {
+            "boolean" => ts_union_type([TS_BOOLEAN_TYPE, TS_NUMBER_TYPE]),
+            "bigint" | "bigint unsigned" | "decimal" => {
+                ts_union_type([TS_NUMBER_TYPE, TS_STRING_TYPE])
+            }
+            "date" | "datetime" | "timestamp" => {
+                ts_union_type([ts_type_ref("Date"), TS_STRING_TYPE])
+            }
+            _ => self.db_type_to_ts_type(ty),
+        }
+    }
+
+    fn d_ts_prefix(&self) -> Vec<ModuleItem> {
+        let prefix = format!(
+            r#"import type {{ ExecutedQuery }} from "@planetscale/database";
+{JSON_VALUE_TYPE}
+"#
+        );
+        let lexer = Lexer::new(
+            Syntax::Typescript(TsSyntax {
+                ..Default::default()
+            }),
+            Default::default(),
+            StringInput::new(&prefix, BytePos(0), BytePos(prefix.len() as u32)),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+        parser.parse_typescript_module().unwrap().body
+    }
+
+    fn d_ts_suffix(&self) -> Vec<ModuleItem> {
+        let execute = r#"execute<T extends keyof Queries>(
+            ...params: Queries[T]["args"] extends never ?
+                [query: T] :
+                [query: T, args: Queries[T]["args"]]
+        ): Promise<ExecutedQuery<Queries[T]["returnType"]>>;"#;
+        let suffix = format!(
+            r#"declare module "@planetscale/database" {{
+    interface Client {{
+        {execute}
+    }}
+    interface Connection {{
+        {execute}
+    }}
+    interface Transaction {{
+        {execute}
+    }}
+}}
+"#
+        );
+        let lexer = Lexer::new(
+            Syntax::Typescript(TsSyntax {
+                no_early_errors: true,
+                ..Default::default()
+            }),
+            Default::default(),
+            StringInput::new(&suffix, BytePos(0), BytePos(suffix.len() as u32)),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+        parser.parse_typescript_module().unwrap().body
+    }
+}
@@ -0,0 +1,297 @@
+use crate::Dialect;
+use crate::describe::DbExt;
+use crate::sql_libs::{Binding, Bindings, SqlLib};
+use crate::utils::ast::{find_config_prop, prop_name, query_string, trailing_name};
+use crate::utils::constants::JSON_VALUE_TYPE;
+use crate::utils::sql_placeholders::{Placeholder, placeholders, replace_placeholders};
+use std::collections::HashMap;
+use swc_common::BytePos;
+use swc_ecma_ast::{CallExpr, Expr, Module, ModuleItem, TsType};
+use swc_ecma_parser::{Lexer, Parser, StringInput, Syntax, TsSyntax};
+
+/// `sequelize.query(...)` calls. Sequelize substitutes `replacements` into the query itself and translates
+/// `bind` parameters into those of the driver, while the column types are those of the driver.
+pub struct Sequelize<Driver> {
+    driver: Driver,
+}
+
+impl<Driver> Sequelize<Driver> {
+    pub fn new(driver: Driver) -> Self {
+        Self { driver }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BindingStyle {
+    Absent,
+    Positional,
+    Named,
+}
+
+/// How the `replacements` or `bind` option is passed. Sequelize decides between positional and named
+/// based on whether the option is an array or an object, and if we can't tell from the call site, we look
+/// at the placeholders in the query instead.
+fn binding_style(
+    options: Option<&Expr>,
+    key: &str,
+    has_positional: bool,
+    has_named: bool,
+) -> BindingStyle {
+    let inferred = if has_named {
+        BindingStyle::Named
+    } else if has_positional {
+        BindingStyle::Positional
+    } else {
+        BindingStyle::Absent
+    };
+    match options {
+        None => BindingStyle::Absent,
+        Some(Expr::Object(object)) => {
+            let value = object.props.iter().find_map(|prop| {
+                let key_value = prop.as_prop()?.as_key_value()?;
+                (prop_name(&key_value.key)? == key).then_some(&*key_value.value)
+            });
+            match value {
+                None => BindingStyle::Absent,
+                Some(Expr::Array(_)) => BindingStyle::Positional,
+                Some(Expr::Object(_)) => BindingStyle::Named,
+                Some(_) => inferred,
+            }
+        }
+        Some(_) => inferred,
+    }
+}
+
+/// Rewrites `replacements` (`?` and `:name`) and `bind` parameters (`$1` and `$name`) into the placeholders
+/// of the dialect
+fn rewrite_sequelize_bindings(
+    dialect: Dialect,
+    options: Option<&Expr>,
+    query: &str,
+) -> Option<(String, Option<Bindings>)> {
+    let found = placeholders(query);
+    let is_numbered = |name: &str| name.bytes().all(|b| b.is_ascii_digit());
+    let replacements_style = binding_style(
+        options,
+        "replacements",
+        found.iter().any(|(_, p)| *p == Placeholder::Question),
+        found
+            .iter()
+            .any(|(_, p)| matches!(p, Placeholder::Named(_))),
+    );
+    let bind_style = binding_style(
+        options,
+        "bind",
+        found
+            .iter()
+            .any(|(_, p)| matches!(p, Placeholder::Dollar(name) if is_numbered(name))),
+        found
+            .iter()
+            .any(|(_, p)| matches!(p, Placeholder::Dollar(name) if !is_numbered(name))),
+    );
+
+    let mut parameter_count = 0;
+    // Postgres can reuse a numbered parameter for repeated names, the other dialects need one per occurrence
+    let mut parameter_indices: HashMap<String, usize> = HashMap::new();
+    let mut parameter_index = |key: String| match dialect {
+        Dialect::Postgres => *parameter_indices.entry(key).or_insert_with(|| {
+            parameter_count += 1;
+            parameter_count - 1
+        }),
+        Dialect::MySql | Dialect::Sqlite => {
+            parameter_count += 1;
+            parameter_count - 1
+        }
+    };
+    let mut positional_replacements = Vec::new();
+    let mut named_replacements = Vec::new();
+    let mut positional_binds: Vec<Binding> = Vec::new();
+    let mut named_binds = Vec::new();
+    let sql = replace_placeholders(query, |placeholder, original| match placeholder {
+        Placeholder::Question if replacements_style == BindingStyle::Positional => {
+            let index = parameter_index(format!("?{}", positional_replacements.len()));
+            positional_replacements.push(Binding::Parameter(index));
+            Some(dialect.placeholder(index))
+        }
+        Placeholder::Named(name) if replacements_style == BindingStyle::Named => {
+            let index = parameter_index(format!(":{name}"));
+            named_replacements.push((name.to_string(), Binding::Parameter(index)));
+            Some(dialect.placeholder(index))
+        }
+        Placeholder::Dollar(number)
+            if bind_style == BindingStyle::Positional && is_numbered(number) =>
+        {
+            let position = number.parse::<usize>().ok()?.checked_sub(1)?;
+            let index = parameter_index(format!("${number}"));
+            if positional_binds.len() <= position {
+                positional_binds.resize(position + 1, Binding::Unused);
+            }
+            if matches!(positional_binds[position], Binding::Unused) {
+                positional_binds[position] = Binding::Parameter(index);
+            }
+            Some(dialect.placeholder(index))
+        }
+        Placeholder::Dollar(name) if bind_style == BindingStyle::Named && !is_numbered(name) => {
+            let index = parameter_index(format!("${name}"));
+            named_binds.push((name.to_string(), Binding::Parameter(index)));
+            Some(dialect.placeholder(index))
+        }
+        _ => Some(original.to_string()),
+    })?;
+
+    let mut options = Vec::new();
+    match replacements_style {
+        BindingStyle::Absent => {}
+        BindingStyle::Positional => options.push((
+            "replacements".to_string(),
+            Bindings::Positional(positional_replacements),
+        )),
+        BindingStyle::Named => options.push((
+            "replacements".to_string(),
+            Bindings::Named(named_replacements),
+        )),
+    }
+    match bind_style {
+        BindingStyle::Absent => {}
+        BindingStyle::Positional => {
+            options.push(("bind".to_string(), Bindings::Positional(positional_binds)))
+        }
+        BindingStyle::Named => options.push(("bind".to_string(), Bindings::Named(named_binds))),
+    }
+
+    Some((sql, Some(Bindings::Options(options))))
+}
+
+impl<Driver: SqlLib> SqlLib for Sequelize<Driver> {
+    type Db = Driver::Db;
+
+    fn parse_call_expr(&self, call_expr: &CallExpr) -> Option<String> {
+        let swc_ecma_ast::Callee::Expr(expr) = &call_expr.callee else {
+            return None;
+        };
+
+        let swc_ecma_ast::Expr::Member(member_expr) = &**expr else {
+            return None;
+        };
+
+        let obj = trailing_name(&member_expr.obj)?;
+        let prop = &member_expr.prop.as_ident()?.sym;
+        if obj != "sequelize" || prop != "query" {
+            return None;
+        }
+
+        let mut args_iter = call_expr.args.iter();
+        let query_expr = args_iter.next()?;
+        let _options = args_iter.next();
+        if args_iter.next().is_some() {
+            return None;
+        }
+
+        if query_expr.spread.is_some() {
+            return None;
+        }
+
+        let query = query_string(&query_expr.expr)?;
+
+        Some(query)
+    }
+
+    fn rewrite_query(
+        &self,
+        call_expr: &CallExpr,
+        query: &str,
+    ) -> Option<(String, Option<Bindings>)> {
+        rewrite_sequelize_bindings(
+            <Driver::Db as DbExt>::DIALECT,
+            call_expr.args.get(1).map(|arg| &*arg.expr),
+            query,
+        )
+    }
+
+    fn db_type_to_ts_type(
+        &self,
+        ty: &<<Self::Db as DbExt>::Db as sqlx::Database>::TypeInfo,
+    ) -> TsType {
+        self.driver.db_type_to_ts_type(ty)
+    }
+
+    fn declared_type_to_ts_type(&self, declared_type: &str, strict: bool) -> Option<TsType> {
+        self.driver.declared_type_to_ts_type(declared_type, strict)
+    }
+
+    fn db_type_to_input_ts_type(
+        &self,
+        ty: &<<Self::Db as DbExt>::Db as sqlx::Database>::TypeInfo,
+    ) -> TsType {
+        self.driver.db_type_to_input_ts_type(ty)
+    }
+
+    fn declared_type_to_input_ts_type(&self, declared_type: &str, strict: bool) -> Option<TsType> {
+        self.driver
+            .declared_type_to_input_ts_type(declared_type, strict)
+    }
+
+    fn d_ts_prefix(&self) -> Vec<ModuleItem> {
+        let prefix = format!(
+            r#"import type {{ QueryOptions, QueryOptionsWithType, QueryTypes }} from "sequelize";
+{JSON_VALUE_TYPE}
+"#
+        );
+        let lexer = Lexer::new(
+            Syntax::Typescript(TsSyntax {
+                ..Default::default()
+            }),
+            Default::default(),
+            StringInput::new(&prefix, BytePos(0), BytePos(prefix.len() as u32)),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+        parser.parse_typescript_module().unwrap().body
+    }
+
+    fn d_ts_suffix(&self) -> Vec<ModuleItem> {
+        // Query types other than SELECT, INSERT and RAW fall through to the overloads of sequelize itself
+        let suffix = r#"declare module "sequelize/types/sequelize" {
+    interface Sequelize {
+        query<T extends keyof Queries>(
+            sql: T,
+            options: Queries[T]["args"] & QueryOptionsWithType<QueryTypes.SELECT> & { plain: true }
+        ): Promise<Queries[T]["returnType"] | null>;
+        query<T extends keyof Queries>(
+            sql: T,
+            options: Queries[T]["args"] & QueryOptionsWithType<QueryTypes.SELECT>
+        ): Promise<Queries[T]["returnType"][]>;
+        query<T extends keyof Queries>(
+            sql: T,
+            options: Queries[T]["args"] & QueryOptionsWithType<QueryTypes.INSERT>
+        ): Promise<[Queries[T]["returnType"][] | number, number]>;
+        query<T extends keyof Queries>(
+            sql: T,
+            options: Queries[T]["args"] & (QueryOptions | QueryOptionsWithType<QueryTypes.RAW>) & { plain: true }
+        ): Promise<Queries[T]["returnType"] | null>;
+        query<T extends keyof Queries>(
+            ...params: {} extends Queries[T]["args"] ?
+                [sql: T, options?: Queries[T]["args"] & (QueryOptions | QueryOptionsWithType<QueryTypes.RAW>)] :
+                [sql: T, options: Queries[T]["args"] & (QueryOptions | QueryOptionsWithType<QueryTypes.RAW>)]
+        ): Promise<[Queries[T]["returnType"][], unknown]>;
+    }
+}
+"#;
+        let lexer = Lexer::new(
+            Syntax::Typescript(TsSyntax {
+                no_early_errors: true,
+                ..Default::default()
+            }),
+            Default::default(),
+            StringInput::new(suffix, BytePos(0), BytePos(suffix.len() as u32)),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+        parser.parse_typescript_module().unwrap().body
+    }
+}
+
+/// Finds the `dialect` configured through `new Sequelize({ dialect })` or `new Sequelize(url, { dialect })`
+pub fn detect_sequelize_dialect(module: &Module) -> Option<String> {
+    find_config_prop(module, &["Sequelize"], "dialect", false)
+}
@@ -0,0 +1,126 @@
+use crate::sql_libs::better_sqlite3::{sqlite_declared_type_to_ts_type, sqlite_type_to_ts_type};
+use crate::sql_libs::expo_sqlite::sqlite_named_bindings;
+use crate::sql_libs::{Bindings, SqlLib};
+use crate::utils::ast::query_string;
+use crate::utils::ts_types::ts_type_ref;
+use swc_common::BytePos;
+use swc_ecma_ast::{CallExpr, ModuleItem, TsType};
+use swc_ecma_parser::{Lexer, Parser, StringInput, Syntax, TsSyntax};
+
+/// `sql.js`, SQLite compiled to WebAssembly
+pub struct SqlJs;
+
+impl SqlLib for SqlJs {
+    type Db = sqlx::Sqlite;
+
+    // `exec` and `Statement.get` return the rows as arrays
+    const RAW_ROWS: bool = true;
+    // `exec` runs every statement of the query, while `prepare` only compiles the first one
+    const MULTIPLE_STATEMENTS: bool = true;
+
+    fn parse_call_expr(&self, call_expr: &CallExpr) -> Option<String> {
+        let swc_ecma_ast::Callee::Expr(expr) = &call_expr.callee else {
+            return None;
+        };
+
+        let swc_ecma_ast::Expr::Member(member_expr) = &**expr else {
+            return None;
+        };
+
+        let obj = &member_expr.obj.as_ident()?.sym;
+        let prop = &member_expr.prop.as_ident()?.sym;
+        if obj != "db" || (prop != "prepare" && prop != "exec") {
+            return None;
+        }
+
+        let mut args_iter = call_expr.args.iter();
+        let query_expr = args_iter.next()?;
+        let _params = args_iter.next();
+        if args_iter.next().is_some() {
+            return None;
+        }
+
+        if query_expr.spread.is_some() {
+            return None;
+        }
+
+        let query = query_string(&query_expr.expr)?;
+
+        Some(query)
+    }
+
+    fn rewrite_query(
+        &self,
+        call_expr: &CallExpr,
+        query: &str,
+    ) -> Option<(String, Option<Bindings>)> {
+        let params_expr = call_expr.args.get(1).map(|arg| &*arg.expr);
+        Some((query.to_string(), sqlite_named_bindings(params_expr, query)))
+    }
+
+    fn db_type_to_ts_type(&self, ty: &<Self::Db as sqlx::Database>::TypeInfo) -> TsType {
+        sqlite_type_to_ts_type(ty, false, ts_type_ref("Uint8Array"))
+    }
+
+    fn declared_type_to_ts_type(&self, declared_type: &str, strict: bool) -> Option<TsType> {
+        Some(sqlite_declared_type_to_ts_type(
+            declared_type,
+            strict,
+            false,
+            ts_type_ref("Uint8Array"),
+        ))
+    }
+
+    fn d_ts_prefix(&self) -> Vec<ModuleItem> {
+        let prefix = r#"import type { Statement } from "sql.js";
+type BindParams<Args> = [Args] extends [never] ? [] : [params: Args];
+type TypedStatement<Args, Row, RawRow> = Omit<Statement, "bind" | "get" | "getAsObject" | "run"> & {
+    bind(...params: BindParams<Args>): boolean;
+    get(...params: Partial<BindParams<Args>>): RawRow;
+    getAsObject(...params: Partial<BindParams<Args>>): Row;
+    run(...params: Partial<BindParams<Args>>): void;
+};
+"#;
+        let lexer = Lexer::new(
+            Syntax::Typescript(TsSyntax {
+                ..Default::default()
+            }),
+            Default::default(),
+            StringInput::new(prefix, BytePos(0), BytePos(prefix.len() as u32)),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+        parser.parse_typescript_module().unwrap().body
+    }
+
+    fn d_ts_suffix(&self) -> Vec<ModuleItem> {
+        let suffix = r#"type Statements<Q> = Q extends { statements: infer S extends unknown[] } ? S : [Q];
+// `exec` gives a result for each statement that returns rows, and leaves out the statements that return no rows,
+// so the results can't be told apart by their position
+type ExecResult<S> = S extends { rawReturnType: infer RawRow extends unknown[] }
+    ? RawRow extends [] ? never : { columns: string[]; values: RawRow[] }
+    : never;
+// `prepare` only compiles the first statement
+type PreparedStatement<Q> = Statements<Q> extends [{ returnType: infer Row; rawReturnType: infer RawRow }, ...unknown[]]
+    ? TypedStatement<Q extends { args: infer Args } ? Args : never, Row, RawRow>
+    : never;
+declare module "sql.js" {
+    interface Database {
+        exec<T extends keyof Queries>(sql: T, ...params: BindParams<Queries[T]["args"]>): ExecResult<Statements<Queries[T]>[number]>[];
+        prepare<T extends keyof Queries>(sql: T, ...params: Partial<BindParams<Queries[T]["args"]>>): PreparedStatement<Queries[T]>;
+    }
+}
+"#;
+        let lexer = Lexer::new(
+            Syntax::Typescript(TsSyntax {
+                no_early_errors: true,
+                ..Default::default()
+            }),
+            Default::default(),
+            StringInput::new(suffix, BytePos(0), BytePos(suffix.len() as u32)),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+        parser.parse_typescript_module().unwrap().body
+    }
+}
@@ -0,0 +1,156 @@
+use crate::describe::DbExt;
+use crate::sql_libs::SqlLib;
+use crate::utils::ast::{find_config_prop, query_string, trailing_name};
+use crate::utils::constants::JSON_VALUE_TYPE;
+use swc_common::BytePos;
+use swc_ecma_ast::{CallExpr, Module, ModuleItem, TsType};
+use swc_ecma_parser::{Lexer, Parser, StringInput, Syntax, TsSyntax};
+
+/// Raw `.query(...)` calls on a TypeORM `DataSource`, `EntityManager` or `QueryRunner`. The query is passed
+/// straight to the driver, so both the placeholders and the column types are those of the driver.
+pub struct TypeOrm<Driver> {
+    driver: Driver,
+}
+
+impl<Driver> TypeOrm<Driver> {
+    pub fn new(driver: Driver) -> Self {
+        Self { driver }
+    }
+}
+
+impl<Driver: SqlLib> SqlLib for TypeOrm<Driver> {
+    type Db = Driver::Db;
+
+    fn parse_call_expr(&self, call_expr: &CallExpr) -> Option<String> {
+        let swc_ecma_ast::Callee::Expr(expr) = &call_expr.callee else {
+            return None;
+        };
+
+        let swc_ecma_ast::Expr::Member(member_expr) = &**expr else {
+            return None;
+        };
+
+        let obj = trailing_name(&member_expr.obj)?;
+        let prop = &member_expr.prop.as_ident()?.sym;
+        if ![
+            "dataSource",
+            "AppDataSource",
+            "entityManager",
+            "manager",
+            "queryRunner",
+        ]
+        .contains(&obj)
+            || prop != "query"
+        {
+            return None;
+        }
+
+        // A third `useStructuredResult` argument changes the result to a `QueryResult`, which we don't type
+        let mut args_iter = call_expr.args.iter();
+        let query_expr = args_iter.next()?;
+        let _parameters = args_iter.next();
+        if args_iter.next().is_some() {
+            return None;
+        }
+
+        if query_expr.spread.is_some() {
+            return None;
+        }
+
+        let query = query_string(&query_expr.expr)?;
+
+        Some(query)
+    }
+
+    fn db_type_to_ts_type(
+        &self,
+        ty: &<<Self::Db as DbExt>::Db as sqlx::Database>::TypeInfo,
+    ) -> TsType {
+        self.driver.db_type_to_ts_type(ty)
+    }
+
+    fn declared_type_to_ts_type(&self, declared_type: &str, strict: bool) -> Option<TsType> {
+        self.driver.declared_type_to_ts_type(declared_type, strict)
+    }
+
+    fn db_type_to_input_ts_type(
+        &self,
+        ty: &<<Self::Db as DbExt>::Db as sqlx::Database>::TypeInfo,
+    ) -> TsType {
+        self.driver.db_type_to_input_ts_type(ty)
+    }
+
+    fn declared_type_to_input_ts_type(&self, declared_type: &str, strict: bool) -> Option<TsType> {
+        self.driver
+            .declared_type_to_input_ts_type(declared_type, strict)
+    }
+
+    fn d_ts_prefix(&self) -> Vec<ModuleItem> {
+        let prefix = format!(
+            r#"import type {{ DataSource }} from "typeorm";
+{JSON_VALUE_TYPE}
+"#
+        );
+        let lexer = Lexer::new(
+            Syntax::Typescript(TsSyntax {
+                ..Default::default()
+            }),
+            Default::default(),
+            StringInput::new(&prefix, BytePos(0), BytePos(prefix.len() as u32)),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+        parser.parse_typescript_module().unwrap().body
+    }
+
+    fn d_ts_suffix(&self) -> Vec<ModuleItem> {
+        let query = r#"query<T extends string>(
+            ...params: T extends keyof Queries ?
+                Queries[T]["args"] extends never ?
+                    [query: T] :
+                    [query: T, parameters: Queries[T]["args"]] :
+                [query: T, parameters?: any[]]
+        ): Promise<T extends keyof Queries ? Queries[T]["returnType"][] : any>;"#;
+        // The classes are augmented where they are declared, as augmentations don't follow re-exports
+        let suffix = format!(
+            r#"declare module "typeorm/data-source/DataSource" {{
+    interface DataSource {{
+        {query}
+    }}
+}}
+declare module "typeorm/entity-manager/EntityManager" {{
+    interface EntityManager {{
+        {query}
+    }}
+}}
+declare module "typeorm/query-runner/QueryRunner" {{
+    interface QueryRunner {{
+        {query}
+    }}
+}}
+"#
+        );
+        let lexer = Lexer::new(
+            Syntax::Typescript(TsSyntax {
+                no_early_errors: true,
+                ..Default::default()
+            }),
+            Default::default(),
+            StringInput::new(&suffix, BytePos(0), BytePos(suffix.len() as u32)),
+            None,
+        );
+        let mut parser = Parser::new_from(lexer);
+        parser.parse_typescript_module().unwrap().body
+    }
+}
+
+/// Finds the `type` of a TypeORM data source, configured through `new DataSource({ type })` or
+/// `createConnection({ type })`, or, if the module is an ormconfig, through any `type` property.
+pub fn detect_typeorm_type(module: &Module, is_ormconfig: bool) -> Option<String> {
+    find_config_prop(
+        module,
+        &["DataSource", "createConnection"],
+        "type",
+        is_ormconfig,
+    )
+}
@@ -0,0 +1,243 @@
+use swc_core::ecma::visit::{Visit, VisitWith as _};
+use swc_ecma_ast::{
+    CallExpr, Callee, Expr, ExprOrSpread, ImportDecl, KeyValueProp, Module, NewExpr, PropName,
+};
+
+pub fn prop_name(key: &PropName) -> Option<&str> {
+    match key {
+        PropName::Ident(ident) => Some(&ident.sym),
+        PropName::Str(str) => Some(&str.value),
+        _ => None,
+    }
+}
+
+/// The value of a string or template literal that a query is passed as. That is the sql at runtime, and the
+/// literal type of the query in TS, so escapes are resolved, e.g. `\n` in a template is a newline.
+pub fn query_string(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Lit(lit) => Some(lit.as_str()?.value.to_string()),
+        Expr::Tpl(tpl) => Some(
+            tpl.quasis
+                .iter()
+                .map(|quasi| quasi.cooked.as_ref().unwrap_or(&quasi.raw).to_string())
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// The value of the property `name` in an object literal
+pub fn object_prop<'a>(expr: &'a Expr, name: &str) -> Option<&'a Expr> {
+    let Expr::Object(object) = expr else {
+        return None;
+    };
+    object.props.iter().find_map(|prop| {
+        let KeyValueProp { key, value } = prop.as_prop()?.as_key_value()?;
+        (prop_name(key)? == name).then_some(&**value)
+    })
+}
+
+/// The string literal value of the property `name` in an object literal
+pub fn object_string_prop(expr: Option<&Expr>, name: &str) -> Option<String> {
+    let Expr::Object(object) = expr? else {
+        return None;
+    };
+    object.props.iter().find_map(|prop| {
+        let KeyValueProp { key, value } = prop.as_prop()?.as_key_value()?;
+        if prop_name(key)? != name {
+            return None;
+        }
+        Some(value.as_lit()?.as_str()?.value.to_string())
+    })
+}
+
+/// The boolean literal value of the property `name` in an object literal
+pub fn object_bool_prop(expr: Option<&Expr>, name: &str) -> Option<bool> {
+    let Expr::Object(object) = expr? else {
+        return None;
+    };
+    object.props.iter().find_map(|prop| {
+        let KeyValueProp { key, value } = prop.as_prop()?.as_key_value()?;
+        if prop_name(key)? != name {
+            return None;
+        }
+        Some(value.as_lit()?.as_bool()?.value)
+    })
+}
+
+/// The string literal value of the element at `index` in an array literal
+pub fn array_string_element(expr: Option<&Expr>, index: usize) -> Option<String> {
+    let Expr::Array(array) = expr? else {
+        return None;
+    };
+    let element = array.elems.get(index)?.as_ref()?;
+    if element.spread.is_some() {
+        return None;
+    }
+    Some(element.expr.as_lit()?.as_str()?.value.to_string())
+}
+
+/// The last name in an identifier or member expression, e.g. `dataSource` in `this.dataSource` and `init`
+/// in `MikroORM.init`
+pub fn trailing_name(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Ident(ident) => Some(&ident.sym),
+        Expr::Member(member_expr) => Some(&member_expr.prop.as_ident()?.sym),
+        _ => None,
+    }
+}
+
+struct ConfigPropVisitor<'a> {
+    callees: &'a [&'a str],
+    prop: &'a str,
+    any_object: bool,
+    value: Option<String>,
+}
+
+impl ConfigPropVisitor<'_> {
+    fn visit_config_call(&mut self, callee: &Expr, args: Option<&[ExprOrSpread]>) {
+        if self.value.is_some() {
+            return;
+        }
+        if trailing_name(callee).is_some_and(|name| self.callees.contains(&name)) {
+            self.value = args.and_then(|args| {
+                args.iter()
+                    .find_map(|arg| object_string_prop(Some(&arg.expr), self.prop))
+            });
+        }
+    }
+}
+
+impl Visit for ConfigPropVisitor<'_> {
+    fn visit_call_expr(&mut self, call_expr: &CallExpr) {
+        if let Callee::Expr(callee) = &call_expr.callee {
+            self.visit_config_call(callee, Some(&call_expr.args));
+        }
+        call_expr.visit_children_with(self);
+    }
+
+    fn visit_new_expr(&mut self, new_expr: &NewExpr) {
+        self.visit_config_call(&new_expr.callee, new_expr.args.as_deref());
+        new_expr.visit_children_with(self);
+    }
+
+    fn visit_key_value_prop(&mut self, prop: &KeyValueProp) {
+        if self.any_object
+            && self.value.is_none()
+            && prop_name(&prop.key) == Some(self.prop)
+            && let Some(value) = prop.value.as_lit().and_then(|lit| lit.as_str())
+        {
+            self.value = Some(value.value.to_string());
+        }
+        prop.visit_children_with(self);
+    }
+}
+
+struct ConfigObjectVisitor<'a> {
+    callees: &'a [&'a str],
+    object: Option<Expr>,
+}
+
+impl ConfigObjectVisitor<'_> {
+    fn visit_config_call(&mut self, callee: &Expr, args: Option<&[ExprOrSpread]>) {
+        if self.object.is_some() {
+            return;
+        }
+        if trailing_name(callee).is_some_and(|name| self.callees.contains(&name)) {
+            self.object = args
+                .and_then(|args| args.iter().find(|arg| arg.expr.is_object()))
+                .map(|arg| (*arg.expr).clone());
+        }
+    }
+}
+
+impl Visit for ConfigObjectVisitor<'_> {
+    fn visit_call_expr(&mut self, call_expr: &CallExpr) {
+        if let Callee::Expr(callee) = &call_expr.callee {
+            self.visit_config_call(callee, Some(&call_expr.args));
+        }
+        call_expr.visit_children_with(self);
+    }
+
+    fn visit_new_expr(&mut self, new_expr: &NewExpr) {
+        self.visit_config_call(&new_expr.callee, new_expr.args.as_deref());
+        new_expr.visit_children_with(self);
+    }
+}
+
+/// Finds the first object literal passed to a call (or `new`) of one of `callees`, e.g. the options in
+/// `mysql.createPool({ host })`
+pub fn find_config_object(module: &Module, callees: &[&str]) -> Option<Expr> {
+    let mut visitor = ConfigObjectVisitor {
+        callees,
+        object: None,
+    };
+    visitor.visit_module(module);
+    visitor.object
+}
+
+/// Finds the string value of `prop` in a config object passed to a call (or `new`) of one of `callees`,
+/// e.g. `client` in `knex({ client: "pg" })` or `dialect` in `new Sequelize(url, { dialect: "postgres" })`.
+/// If `any_object` is set, which is useful for dedicated config files, the property is looked for in every
+/// object literal in the module.
+pub fn find_config_prop(
+    module: &Module,
+    callees: &[&str],
+    prop: &str,
+    any_object: bool,
+) -> Option<String> {
+    let mut visitor = ConfigPropVisitor {
+        callees,
+        prop,
+        any_object,
+        value: None,
+    };
+    visitor.visit_module(module);
+    visitor.value
+}
+
+struct ImportVisitor<'a> {
+    package: &'a str,
+    imported: bool,
+}
+
+impl ImportVisitor<'_> {
+    /// Whether the import path is the package or a path in it, like `mysql2/promise`
+    fn is_package(&self, path: &str) -> bool {
+        path.strip_prefix(self.package)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    }
+}
+
+impl Visit for ImportVisitor<'_> {
+    fn visit_import_decl(&mut self, import_decl: &ImportDecl) {
+        self.imported |= self.is_package(&import_decl.src.value);
+    }
+
+    fn visit_call_expr(&mut self, call_expr: &CallExpr) {
+        let is_import = match &call_expr.callee {
+            Callee::Import(_) => true,
+            Callee::Expr(callee) => callee
+                .as_ident()
+                .is_some_and(|ident| ident.sym == "require"),
+            Callee::Super(_) => false,
+        };
+        if is_import
+            && let Some(arg) = call_expr.args.first()
+            && query_string(&arg.expr).is_some_and(|path| self.is_package(&path))
+        {
+            self.imported = true;
+        }
+        call_expr.visit_children_with(self);
+    }
+}
+
+/// Whether the module imports the package, or a path in it, with an `import` declaration, `require` or a dynamic `import()`
+pub fn imports_package(module: &Module, package: &str) -> bool {
+    let mut visitor = ImportVisitor {
+        package,
+        imported: false,
+    };
+    visitor.visit_module(module);
+    visitor.imported
+}
@@ -1,3 +1,7 @@
 pub const JSON_VALUE_TYPE: &str = r#"type JsonValue = string | number | boolean | null | {
     [Key in string]?: JsonValue;
 } | JsonValue[];"#;
+
+/// The shapes spatial values are decoded into by `mysql` and `mysql2`
+pub const GEOMETRY_TYPE: &str = r#"type Point = { x: number; y: number };
+type Geometry = Point | Point[] | Point[][] | Point[][][] | Geometry[];"#;
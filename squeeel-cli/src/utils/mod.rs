@@ -1,2 +1,7 @@
+pub mod ast;
 pub mod constants;
+pub mod param_types;
+pub mod result_columns;
+pub mod sql_placeholders;
+pub mod statements;
 pub mod ts_types;
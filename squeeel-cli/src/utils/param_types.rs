@@ -0,0 +1,454 @@
+use crate::Dialect;
+use crate::utils::sql_placeholders::{Placeholder, placeholders};
+use crate::utils::statements::parse_sql;
+use sqlparser::ast::{
+    Assignment, AssignmentTarget, BinaryOperator, Expr, FunctionArg, FunctionArgExpr,
+    FunctionArguments, Ident, ObjectName, OnConflictAction, OnInsert, Query, SetExpr, Statement,
+    TableFactor, Value, Visit, Visitor,
+};
+use std::collections::HashMap;
+use std::ops::{ControlFlow, Range};
+
+/// Where a parameter of a query is used, which decides the type of its value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamContext {
+    /// Compared with a column of a table, or assigned to or inserted into one. The table is lowercased, and
+    /// the column is named as in the table.
+    Column {
+        table: String,
+        column: String,
+        assigned: bool,
+    },
+    /// The pattern of a `LIKE`
+    Pattern,
+    /// A number of rows, like `LIMIT ?`
+    Count,
+    /// Where `null` is a meaningful value, like `IS NOT DISTINCT FROM ?` or `COALESCE(?, ...)`
+    Null,
+    /// A name given in a comment after the placeholder, like `$1::int /* minAge */`
+    Named(String),
+}
+
+/// A use of a parameter, before its column references are resolved against the tables of the query
+enum ParamUse {
+    Column { parts: Vec<String>, assigned: bool },
+    Pattern,
+    Count,
+    Null,
+}
+
+/// The (one based) number of each placeholder, the way the database numbers them. SQLite gives a plain `?`
+/// the number after the largest so far and a name the number of its first occurrence, MySQL only knows `?`,
+/// and Postgres placeholders are numbered already.
+fn placeholder_numbers(sql: &str, dialect: Dialect) -> Vec<(Range<usize>, usize)> {
+    let bytes = sql.as_bytes();
+    let mut numbers = Vec::new();
+    let mut largest = 0;
+    let mut names: HashMap<&str, usize> = HashMap::new();
+    for (range, placeholder) in placeholders(sql) {
+        let (number, end) = match (dialect, placeholder) {
+            (Dialect::Sqlite, Placeholder::Question) => {
+                let mut end = range.end;
+                while end < bytes.len() && bytes[end].is_ascii_digit() {
+                    end += 1;
+                }
+                let number = sql[range.end..end].parse().unwrap_or(largest + 1);
+                (number, end)
+            }
+            (Dialect::Sqlite, Placeholder::Named(_) | Placeholder::Dollar(_)) => {
+                let next = largest + 1;
+                (*names.entry(&sql[range.clone()]).or_insert(next), range.end)
+            }
+            (Dialect::MySql, Placeholder::Question) => (largest + 1, range.end),
+            (Dialect::Postgres, Placeholder::Dollar(number)) => match number.parse() {
+                Ok(number) => (number, range.end),
+                Err(_) => continue,
+            },
+            _ => continue,
+        };
+        largest = largest.max(number);
+        numbers.push((range.start..end, number));
+    }
+
+    numbers
+}
+
+/// Numbers the placeholders, so the parsed query tells which parameter each one is. `?` is an operator in
+/// Postgres, so its placeholders keep their `$`.
+fn number_placeholders(sql: &str, dialect: Dialect, numbers: &[(Range<usize>, usize)]) -> String {
+    let prefix = match dialect {
+        Dialect::Postgres => '$',
+        Dialect::MySql | Dialect::Sqlite => '?',
+    };
+    let mut numbered = String::with_capacity(sql.len());
+    let mut last = 0;
+    for (range, number) in numbers {
+        numbered.push_str(&sql[last..range.start]);
+        numbered.push_str(&format!("{prefix}{number}"));
+        last = range.end;
+    }
+    numbered.push_str(&sql[last..]);
+
+    numbered
+}
+
+/// The name in a comment right after a placeholder, or after its cast, like `$1::int /* minAge */`
+fn comment_name(rest: &str) -> Option<String> {
+    let mut rest = rest;
+    if let Some(cast) = rest.strip_prefix("::") {
+        let end = cast
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '[' | ']')))
+            .unwrap_or(cast.len());
+        rest = &cast[end..];
+    }
+    let comment = rest.trim_start().strip_prefix("/*")?;
+    let name = comment[..comment.find("*/")?].trim();
+    let is_identifier = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    is_identifier.then(|| name.to_string())
+}
+
+/// The zero based index of a numbered placeholder, which can be cast, like `$1::int`
+fn param_index(expr: &Expr) -> Option<usize> {
+    let mut expr = expr;
+    while let Expr::Nested(inner) | Expr::Cast { expr: inner, .. } = expr {
+        expr = inner;
+    }
+    let Expr::Value(Value::Placeholder(placeholder)) = expr else {
+        return None;
+    };
+    placeholder
+        .strip_prefix(['?', '$'])?
+        .parse::<usize>()
+        .ok()?
+        .checked_sub(1)
+}
+
+pub(super) fn column_parts(expr: &Expr) -> Option<Vec<String>> {
+    let mut expr = expr;
+    while let Expr::Nested(nested) = expr {
+        expr = nested;
+    }
+    match expr {
+        Expr::Identifier(ident) => Some(vec![ident.value.to_lowercase()]),
+        Expr::CompoundIdentifier(idents) => Some(
+            idents
+                .iter()
+                .map(|ident| ident.value.to_lowercase())
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+fn object_name_parts(name: &ObjectName) -> Vec<String> {
+    name.0
+        .iter()
+        .map(|ident| ident.value.to_lowercase())
+        .collect()
+}
+
+pub(super) fn table_name(name: &ObjectName) -> String {
+    name.0
+        .last()
+        .map(|ident| ident.value.to_lowercase())
+        .unwrap_or_default()
+}
+
+struct ParamVisitor<'a> {
+    columns: &'a HashMap<String, Vec<String>>,
+    /// The tables of the query with their aliases
+    tables: Vec<(String, Option<String>)>,
+    uses: Vec<(usize, ParamUse)>,
+}
+
+impl ParamVisitor<'_> {
+    fn compare(&mut self, column: &Expr, param: &Expr) {
+        if let (Some(parts), Some(index)) = (column_parts(column), param_index(param)) {
+            let assigned = false;
+            self.uses
+                .push((index, ParamUse::Column { parts, assigned }));
+        }
+    }
+
+    fn null(&mut self, param: &Expr) {
+        if let Some(index) = param_index(param) {
+            self.uses.push((index, ParamUse::Null));
+        }
+    }
+
+    fn count(&mut self, param: &Expr) {
+        if let Some(index) = param_index(param) {
+            self.uses.push((index, ParamUse::Count));
+        }
+    }
+
+    fn assignments(&mut self, assignments: &[Assignment]) {
+        for assignment in assignments {
+            if let (AssignmentTarget::ColumnName(name), Some(index)) =
+                (&assignment.target, param_index(&assignment.value))
+            {
+                let parts = object_name_parts(name);
+                let assigned = true;
+                self.uses
+                    .push((index, ParamUse::Column { parts, assigned }));
+            }
+        }
+    }
+
+    fn insert(&mut self, table: &str, columns: &[Ident], source: &Query) {
+        let SetExpr::Values(values) = &*source.body else {
+            return;
+        };
+        // Without a column list the values are in the order of the table columns
+        let columns: Vec<String> = if columns.is_empty() {
+            self.columns
+                .get(table)
+                .into_iter()
+                .flatten()
+                .map(|column| column.to_lowercase())
+                .collect()
+        } else {
+            columns
+                .iter()
+                .map(|column| column.value.to_lowercase())
+                .collect()
+        };
+        for row in &values.rows {
+            for (value, column) in row.iter().zip(&columns) {
+                if let Some(index) = param_index(value) {
+                    let parts = vec![table.to_string(), column.clone()];
+                    let assigned = true;
+                    self.uses
+                        .push((index, ParamUse::Column { parts, assigned }));
+                }
+            }
+        }
+    }
+
+    /// The table column a column reference is to. An unqualified column must be a column of exactly one of
+    /// the tables of the query.
+    fn resolve_column(&self, parts: &[String], assigned: bool) -> Option<ParamContext> {
+        let (column, qualifier) = match parts {
+            [column] => (column, None),
+            [.., qualifier, column] => (column, Some(qualifier)),
+            [] => return None,
+        };
+        // The column as it is named in the table
+        let table_column = |table: &str| {
+            self.columns
+                .get(table)?
+                .iter()
+                .find(|name| name.to_lowercase() == *column)
+        };
+        let mut candidates: Vec<(&str, &String)> = self
+            .tables
+            .iter()
+            .filter(|(table, alias)| match qualifier {
+                Some(qualifier) => alias.as_ref().unwrap_or(table) == qualifier,
+                None => true,
+            })
+            .filter_map(|(table, _)| Some((table.as_str(), table_column(table)?)))
+            .collect();
+        candidates.sort();
+        candidates.dedup();
+        match candidates[..] {
+            [(table, column)] => Some(ParamContext::Column {
+                table: table.to_string(),
+                column: column.clone(),
+                assigned,
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Visitor for ParamVisitor<'_> {
+    type Break = ();
+
+    fn pre_visit_statement(&mut self, statement: &Statement) -> ControlFlow<()> {
+        match statement {
+            Statement::Insert(insert) => {
+                let table = table_name(&insert.table_name);
+                self.tables.push((table.clone(), None));
+                if let Some(source) = &insert.source {
+                    self.insert(&table, &insert.columns, source);
+                }
+                match &insert.on {
+                    Some(OnInsert::DuplicateKeyUpdate(assignments)) => {
+                        self.assignments(assignments)
+                    }
+                    Some(OnInsert::OnConflict(on_conflict)) => {
+                        if let OnConflictAction::DoUpdate(update) = &on_conflict.action {
+                            self.assignments(&update.assignments);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Statement::Update { assignments, .. } => self.assignments(assignments),
+            Statement::Delete(delete) => {
+                if let Some(limit) = &delete.limit {
+                    self.count(limit);
+                }
+            }
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_query(&mut self, query: &Query) -> ControlFlow<()> {
+        if let Some(limit) = &query.limit {
+            self.count(limit);
+        }
+        if let Some(offset) = &query.offset {
+            self.count(&offset.value);
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_table_factor(&mut self, table_factor: &TableFactor) -> ControlFlow<()> {
+        if let TableFactor::Table { name, alias, .. } = table_factor {
+            let alias = alias.as_ref().map(|alias| alias.name.value.to_lowercase());
+            self.tables.push((table_name(name), alias));
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<()> {
+        match expr {
+            Expr::BinaryOp {
+                left,
+                op:
+                    BinaryOperator::Eq
+                    | BinaryOperator::NotEq
+                    | BinaryOperator::Lt
+                    | BinaryOperator::LtEq
+                    | BinaryOperator::Gt
+                    | BinaryOperator::GtEq,
+                right,
+            } => {
+                self.compare(left, right);
+                self.compare(right, left);
+            }
+            // `<=>` is the `IS NOT DISTINCT FROM` of MySQL
+            Expr::BinaryOp {
+                left,
+                op: BinaryOperator::Spaceship,
+                right,
+            } => {
+                self.compare(left, right);
+                self.compare(right, left);
+                self.null(left);
+                self.null(right);
+            }
+            Expr::IsDistinctFrom(left, right) | Expr::IsNotDistinctFrom(left, right) => {
+                // The parser takes the rest of a condition as the right operand, as in `a IS NOT DISTINCT
+                // FROM (? AND b = 1)`
+                let mut right = &**right;
+                while let Expr::BinaryOp {
+                    left,
+                    op: BinaryOperator::And | BinaryOperator::Or,
+                    ..
+                } = right
+                {
+                    right = left;
+                }
+                self.compare(left, right);
+                self.compare(right, left);
+                self.null(left);
+                self.null(right);
+            }
+            // The arguments of `COALESCE` and `IFNULL` can be null, and have the type of the others
+            Expr::Function(function)
+                if function.name.0.last().is_some_and(|name| {
+                    matches!(name.value.to_lowercase().as_str(), "coalesce" | "ifnull")
+                }) =>
+            {
+                let FunctionArguments::List(list) = &function.args else {
+                    return ControlFlow::Continue(());
+                };
+                let args: Vec<&Expr> = list
+                    .args
+                    .iter()
+                    .filter_map(|arg| match arg {
+                        FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => Some(expr),
+                        _ => None,
+                    })
+                    .collect();
+                for param in &args {
+                    self.null(param);
+                    for column in &args {
+                        self.compare(column, param);
+                    }
+                }
+            }
+            Expr::Like { pattern, .. } | Expr::ILike { pattern, .. } => {
+                if let Some(index) = param_index(pattern) {
+                    self.uses.push((index, ParamUse::Pattern));
+                }
+            }
+            Expr::Between {
+                expr, low, high, ..
+            } => {
+                self.compare(expr, low);
+                self.compare(expr, high);
+            }
+            Expr::InList { expr, list, .. } => {
+                for item in list {
+                    self.compare(expr, item);
+                }
+            }
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// Finds where each parameter of a query is used from the query itself, as MySQL and SQLite do not describe
+/// the types of their parameters, and no database tells whether a parameter can be null or what it stands
+/// for. `columns` are the column names of each table, in order, keyed by the lowercased table name. The uses
+/// are in the order they appear in the query.
+pub fn param_contexts(
+    sql: &str,
+    dialect: Dialect,
+    columns: &HashMap<String, Vec<String>>,
+    count: usize,
+) -> Vec<Vec<ParamContext>> {
+    let mut contexts = vec![Vec::new(); count];
+    let numbers = placeholder_numbers(sql, dialect);
+    for (range, number) in &numbers {
+        if let (Some(name), Some(param_contexts)) = (
+            comment_name(&sql[range.end..]),
+            contexts.get_mut(number.wrapping_sub(1)),
+        ) {
+            param_contexts.push(ParamContext::Named(name));
+        }
+    }
+
+    let sql = number_placeholders(sql, dialect, &numbers);
+    let Ok(statements) = parse_sql(&sql, dialect) else {
+        return contexts;
+    };
+
+    let mut visitor = ParamVisitor {
+        columns,
+        tables: Vec::new(),
+        uses: Vec::new(),
+    };
+    let _ = statements.visit(&mut visitor);
+    for (index, param_use) in &visitor.uses {
+        let Some(param_contexts) = contexts.get_mut(*index) else {
+            continue;
+        };
+        let context = match param_use {
+            ParamUse::Column { parts, assigned } => visitor.resolve_column(parts, *assigned),
+            ParamUse::Pattern => Some(ParamContext::Pattern),
+            ParamUse::Count => Some(ParamContext::Count),
+            ParamUse::Null => Some(ParamContext::Null),
+        };
+        param_contexts.extend(context);
+    }
+
+    contexts
+}
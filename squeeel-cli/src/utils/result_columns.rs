@@ -0,0 +1,99 @@
+use crate::Dialect;
+use crate::utils::param_types::{column_parts, table_name};
+use crate::utils::statements::parse_sql;
+use sqlparser::ast::{SelectItem, SetExpr, Statement, TableFactor};
+use std::collections::HashMap;
+
+/// The table column that each result column of a query comes from, as `(table, column)`, for databases that
+/// don't tell it. Column references, like `u.name`, and wildcards are resolved against the tables in the
+/// `FROM` of the query, and other columns have no origin. `columns` are the column names of each table, in
+/// order, keyed by the lowercased table name. `None` if the query is not a `SELECT` that can be parsed, or its
+/// wildcards can't be expanded.
+pub fn result_column_origins(
+    sql: &str,
+    dialect: Dialect,
+    columns: &HashMap<String, Vec<String>>,
+) -> Option<Vec<Option<(String, String)>>> {
+    let statements = parse_sql(sql, dialect).ok()?;
+    let [Statement::Query(query)] = &statements[..] else {
+        return None;
+    };
+    let SetExpr::Select(select) = query.body.as_ref() else {
+        return None;
+    };
+
+    // The tables of the query with their aliases. The columns of subqueries and table functions are unknown.
+    let relations = select.from.iter().flat_map(|table| {
+        std::iter::once(&table.relation).chain(table.joins.iter().map(|join| &join.relation))
+    });
+    let mut tables: Vec<(String, Option<String>)> = Vec::new();
+    let mut only_tables = true;
+    for relation in relations {
+        match relation {
+            TableFactor::Table { name, alias, .. } => {
+                let alias = alias.as_ref().map(|alias| alias.name.value.to_lowercase());
+                tables.push((table_name(name), alias));
+            }
+            _ => only_tables = false,
+        }
+    }
+    let table_columns = |table: &String| {
+        columns
+            .get(table)
+            .into_iter()
+            .flatten()
+            .map(|column| Some((table.clone(), column.clone())))
+            .collect::<Vec<_>>()
+    };
+    // The table column of a column reference. An unqualified column must be a column of exactly one of the
+    // tables of the query.
+    let resolve = |parts: &[String]| {
+        let (column, qualifier) = match parts {
+            [column] => (column, None),
+            [.., qualifier, column] => (column, Some(qualifier)),
+            [] => return None,
+        };
+        let mut candidates: Vec<(&String, &String)> = tables
+            .iter()
+            .filter(|(table, alias)| match qualifier {
+                Some(qualifier) => alias.as_ref().unwrap_or(table) == qualifier,
+                None => true,
+            })
+            .filter_map(|(table, _)| {
+                let table_column = columns
+                    .get(table)?
+                    .iter()
+                    .find(|name| name.to_lowercase() == *column)?;
+                Some((table, table_column))
+            })
+            .collect();
+        candidates.sort();
+        candidates.dedup();
+        match candidates[..] {
+            [(table, column)] => Some((table.clone(), column.clone())),
+            _ => None,
+        }
+    };
+
+    let mut origins = Vec::new();
+    for item in &select.projection {
+        match item {
+            SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                origins.push(column_parts(expr).and_then(|parts| resolve(&parts)));
+            }
+            SelectItem::Wildcard(_) if only_tables => {
+                origins.extend(tables.iter().flat_map(|(table, _)| table_columns(table)));
+            }
+            SelectItem::Wildcard(_) => return None,
+            SelectItem::QualifiedWildcard(name, _) => {
+                let qualifier = table_name(name);
+                let (table, _) = tables
+                    .iter()
+                    .find(|(table, alias)| alias.as_ref().unwrap_or(table) == &qualifier)?;
+                origins.extend(table_columns(table));
+            }
+        }
+    }
+
+    Some(origins)
+}
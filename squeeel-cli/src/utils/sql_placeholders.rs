@@ -0,0 +1,152 @@
+use std::ops::Range;
+
+/// A placeholder found in a sql string, outside of string literals, quoted identifiers and comments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Placeholder<'a> {
+    /// `?`
+    Question,
+    /// `??`
+    DoubleQuestion,
+    /// `\?`, a question mark that has been escaped so it is not treated as a placeholder
+    EscapedQuestion,
+    /// `:name`
+    Named(&'a str),
+    /// `:name:`
+    NamedIdentifier(&'a str),
+    /// `$1` or `$name`
+    Dollar(&'a str),
+}
+
+fn is_ident_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+fn ident_end(bytes: &[u8], start: usize) -> usize {
+    let mut end = start;
+    while end < bytes.len() && is_ident_byte(bytes[end]) {
+        end += 1;
+    }
+    end
+}
+
+/// Finds the end of a quoted section starting at `start`, where the quote is escaped by doubling it.
+fn quoted_end(bytes: &[u8], start: usize, quote: u8) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        if bytes[i] == quote {
+            if bytes.get(i + 1) == Some(&quote) {
+                i += 2;
+                continue;
+            }
+            return i + 1;
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// The end of the string literal, quoted identifier, comment or dollar quoted string that starts at `i`, or
+/// `None` if there is none there.
+pub fn skipped_end(sql: &str, i: usize) -> Option<usize> {
+    let bytes = sql.as_bytes();
+    match bytes[i] {
+        quote @ (b'\'' | b'"' | b'`') => Some(quoted_end(bytes, i, quote)),
+        b'-' if bytes.get(i + 1) == Some(&b'-') => {
+            Some(sql[i..].find('\n').map_or(bytes.len(), |end| i + end + 1))
+        }
+        b'/' if bytes.get(i + 1) == Some(&b'*') => Some(
+            sql[i + 2..]
+                .find("*/")
+                .map_or(bytes.len(), |end| i + 2 + end + 2),
+        ),
+        b'$' => {
+            let end = ident_end(bytes, i + 1);
+            // A dollar quoted string, like $$...$$ or $tag$...$tag$
+            (bytes.get(end) == Some(&b'$')).then(|| {
+                let tag = &sql[i..=end];
+                sql[end + 1..]
+                    .find(tag)
+                    .map_or(bytes.len(), |close| end + 1 + close + tag.len())
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Scans the sql for placeholders, skipping string literals, quoted identifiers, comments, dollar quoted
+/// strings and `::` casts.
+pub fn placeholders(sql: &str) -> Vec<(Range<usize>, Placeholder<'_>)> {
+    let bytes = sql.as_bytes();
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if let Some(end) = skipped_end(sql, i) {
+            i = end;
+            continue;
+        }
+        match bytes[i] {
+            b'\\' if bytes.get(i + 1) == Some(&b'?') => {
+                found.push((i..i + 2, Placeholder::EscapedQuestion));
+                i += 2;
+            }
+            b'?' if bytes.get(i + 1) == Some(&b'?') => {
+                found.push((i..i + 2, Placeholder::DoubleQuestion));
+                i += 2;
+            }
+            b'?' => {
+                found.push((i..i + 1, Placeholder::Question));
+                i += 1;
+            }
+            b':' if bytes.get(i + 1) == Some(&b':') => i += 2,
+            b':' => {
+                let end = ident_end(bytes, i + 1);
+                if end == i + 1 {
+                    i += 1;
+                    continue;
+                }
+                let name = &sql[i + 1..end];
+                let rest = &bytes[end..];
+                // `:name:` is an identifier, unless the trailing colon is the start of a `::` cast
+                if rest.starts_with(b":::") || (rest.starts_with(b":") && !rest.starts_with(b"::"))
+                {
+                    found.push((i..end + 1, Placeholder::NamedIdentifier(name)));
+                    i = end + 1;
+                } else {
+                    found.push((i..end, Placeholder::Named(name)));
+                    i = end;
+                }
+            }
+            b'$' => {
+                let end = ident_end(bytes, i + 1);
+                if end > i + 1 {
+                    found.push((i..end, Placeholder::Dollar(&sql[i + 1..end])));
+                    i = end;
+                } else {
+                    i += 1;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    found
+}
+
+/// Replaces every placeholder in the sql by the output of `replace`, which is also given the original text of
+/// the placeholder. If `replace` returns `None` for any placeholder, the rewrite is aborted and `None` is
+/// returned.
+pub fn replace_placeholders<'a, F>(sql: &'a str, mut replace: F) -> Option<String>
+where
+    F: FnMut(&Placeholder<'a>, &'a str) -> Option<String>,
+{
+    let mut rewritten = String::with_capacity(sql.len());
+    let mut last = 0;
+    for (range, placeholder) in placeholders(sql) {
+        rewritten.push_str(&sql[last..range.start]);
+        rewritten.push_str(&replace(&placeholder, &sql[range.clone()])?);
+        last = range.end;
+    }
+    rewritten.push_str(&sql[last..]);
+
+    Some(rewritten)
+}
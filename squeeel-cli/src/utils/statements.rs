@@ -0,0 +1,123 @@
+use crate::Dialect;
+use crate::utils::sql_placeholders::skipped_end;
+use sqlparser::ast::{SetExpr, Statement};
+use sqlparser::dialect::{MySqlDialect, PostgreSqlDialect, SQLiteDialect};
+use sqlparser::parser::{Parser, ParserError};
+
+/// Parses sql with the dialect of the database
+pub fn parse_sql(sql: &str, dialect: Dialect) -> Result<Vec<Statement>, ParserError> {
+    match dialect {
+        Dialect::MySql => Parser::parse_sql(&MySqlDialect {}, sql),
+        Dialect::Sqlite => Parser::parse_sql(&SQLiteDialect {}, sql),
+        Dialect::Postgres => Parser::parse_sql(&PostgreSqlDialect {}, sql),
+    }
+}
+
+/// The sql after its leading whitespace and comments
+fn skip_comments(sql: &str) -> &str {
+    let mut rest = sql;
+    loop {
+        rest = rest.trim_start();
+        if let Some(comment) = rest.strip_prefix("--") {
+            rest = comment.split_once('\n').map_or("", |(_, rest)| rest);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.split_once("*/").map_or("", |(_, rest)| rest);
+        } else {
+            return rest;
+        }
+    }
+}
+
+/// The first keyword of the sql, skipping whitespace and comments
+fn first_keyword(sql: &str) -> &str {
+    let rest = skip_comments(sql);
+    let end = rest
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(rest.len());
+    &rest[..end]
+}
+
+/// Splits sql into its statements at the semicolons between them, without the semicolons. Statements that are
+/// only whitespace and comments, like after a trailing semicolon, are left out.
+pub fn split_statements(sql: &str) -> Vec<&str> {
+    let bytes = sql.as_bytes();
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if let Some(end) = skipped_end(sql, i) {
+            i = end;
+            continue;
+        }
+        if bytes[i] == b';' {
+            statements.push(&sql[start..i]);
+            start = i + 1;
+        }
+        i += 1;
+    }
+    statements.push(&sql[start..]);
+    statements.retain(|statement| !skip_comments(statement).is_empty());
+
+    statements
+}
+
+/// The name given to a query in a comment, like `/* @name getUserById */` or `-- @name getUserById`
+pub fn query_name(sql: &str) -> Option<String> {
+    let mut i = 0;
+    while i < sql.len() {
+        let Some(end) = skipped_end(sql, i) else {
+            i += 1;
+            continue;
+        };
+        let comment = &sql[i..end];
+        if comment.starts_with("/*") || comment.starts_with("--") {
+            let name = comment
+                .split_once("@name")
+                .map(|(_, rest)| rest.trim_start())
+                .map(|rest| {
+                    let end = rest
+                        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                        .unwrap_or(rest.len());
+                    &rest[..end]
+                })
+                .filter(|name| !name.is_empty());
+            if let Some(name) = name {
+                return Some(name.to_string());
+            }
+        }
+        i = end;
+    }
+
+    None
+}
+
+fn statement_command(statement: &Statement) -> Option<&'static str> {
+    match statement {
+        Statement::Query(query) => match query.body.as_ref() {
+            SetExpr::Insert(statement) | SetExpr::Update(statement) => statement_command(statement),
+            _ => Some("SELECT"),
+        },
+        Statement::Insert(_) => Some("INSERT"),
+        Statement::Update { .. } => Some("UPDATE"),
+        Statement::Delete(_) => Some("DELETE"),
+        Statement::Merge { .. } => Some("MERGE"),
+        _ => None,
+    }
+}
+
+/// The command of the statement, like `INSERT`, as Postgres reports it after running it. That is the
+/// first keyword of the statement, except for the statements that return rows, which are a `SELECT`, and
+/// those that start with a `WITH`, which are the command of their main statement. `None` if the command
+/// can't be told.
+pub fn command(sql: &str, dialect: Dialect) -> Option<String> {
+    let parsed = parse_sql(sql, dialect)
+        .ok()
+        .and_then(|statements| statements.first().and_then(statement_command));
+    if let Some(command) = parsed {
+        return Some(command.to_string());
+    }
+    match first_keyword(sql).to_uppercase() {
+        keyword if keyword.is_empty() || keyword == "WITH" => None,
+        keyword => Some(keyword),
+    }
+}
@@ -1,9 +1,12 @@
 use swc_atoms::Atom;
-use swc_common::DUMMY_SP;
+use swc_common::{BytePos, DUMMY_SP};
 use swc_ecma_ast::{
-    Expr, Ident, TsKeywordType, TsPropertySignature, TsTupleElement, TsTupleType, TsType,
-    TsTypeAnn, TsTypeElement, TsTypeLit, TsTypeRef, TsUnionOrIntersectionType, TsUnionType,
+    BindingIdent, Decl, Expr, Ident, ModuleItem, Pat, Stmt, Tpl, TplElement, TsArrayType,
+    TsKeywordType, TsLit, TsLitType, TsParenthesizedType, TsPropertySignature, TsTupleElement,
+    TsTupleType, TsType, TsTypeAnn, TsTypeElement, TsTypeLit, TsTypeRef, TsUnionOrIntersectionType,
+    TsUnionType,
 };
+use swc_ecma_parser::{Lexer, Parser, StringInput, Syntax, TsSyntax};
 
 pub const TS_BOOLEAN_TYPE: TsType = TsType::TsKeywordType(TsKeywordType {
     span: DUMMY_SP,
@@ -40,16 +43,35 @@ pub const TS_UNDEFINED_TYPE: TsType = TsType::TsKeywordType(TsKeywordType {
 //     kind: swc_ecma_ast::TsKeywordTypeKind::TsAnyKeyword,
 // });
 
-// pub const TS_BIGINT_TYPE: TsType = TsType::TsKeywordType(TsKeywordType {
-//     span: DUMMY_SP,
-//     kind: swc_ecma_ast::TsKeywordTypeKind::TsBigIntKeyword,
-// });
+pub const TS_BIGINT_TYPE: TsType = TsType::TsKeywordType(TsKeywordType {
+    span: DUMMY_SP,
+    kind: swc_ecma_ast::TsKeywordTypeKind::TsBigIntKeyword,
+});
 
 pub const TS_UNKNOWN_TYPE: TsType = TsType::TsKeywordType(TsKeywordType {
     span: DUMMY_SP,
     kind: swc_ecma_ast::TsKeywordTypeKind::TsUnknownKeyword,
 });
 
+/// Converts a database name, like `order_status`, into a TS name, like `OrderStatus`
+pub fn ts_type_name(name: &str) -> String {
+    let mut ts_name = String::with_capacity(name.len());
+    for word in name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            ts_name.push(first.to_ascii_uppercase());
+            ts_name.push_str(chars.as_str());
+        }
+    }
+    if !ts_name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        ts_name.insert(0, '_');
+    }
+    ts_name
+}
+
 pub fn ts_type_ref<Sym: Into<Atom>>(sym: Sym) -> TsType {
     TsType::TsTypeRef(TsTypeRef {
         span: DUMMY_SP,
@@ -58,6 +80,8 @@ pub fn ts_type_ref<Sym: Into<Atom>>(sym: Sym) -> TsType {
     })
 }
 
+/// An object type with the given members. Names converted from strings are string literal keys, which the
+/// emitter quotes and escapes, so any column name is a valid key, like `"order-count"` or `"count(*)"`.
 pub fn ts_object_type<Members: IntoIterator<Item = (Expr, TsType, bool)>>(
     members: Members,
 ) -> TsType {
@@ -106,12 +130,19 @@ pub fn ts_object_type_computed<Members: IntoIterator<Item = (Expr, TsType, bool)
     })
 }
 
-// pub fn ts_nullable_type(ty: TsType) -> TsType {
-//     TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(TsUnionType {
-//         span: DUMMY_SP,
-//         types: vec![Box::new(ty), Box::new(TS_NULL_TYPE)],
-//     }))
-// }
+pub fn ts_nullable_type(ty: TsType) -> TsType {
+    TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(TsUnionType {
+        span: DUMMY_SP,
+        types: vec![Box::new(ty), Box::new(TS_NULL_TYPE)],
+    }))
+}
+
+pub fn ts_union_type<Types: IntoIterator<Item = TsType>>(types: Types) -> TsType {
+    TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(TsUnionType {
+        span: DUMMY_SP,
+        types: types.into_iter().map(Box::new).collect(),
+    }))
+}
 
 pub fn ts_optional_type(ty: TsType) -> TsType {
     TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(TsUnionType {
@@ -133,3 +164,97 @@ pub fn ts_tuple_type<ElemTypes: IntoIterator<Item = TsType>>(types: ElemTypes) -
             .collect(),
     })
 }
+
+pub fn ts_labeled_tuple_type<ElemTypes: IntoIterator<Item = (String, TsType)>>(
+    types: ElemTypes,
+) -> TsType {
+    TsType::TsTupleType(TsTupleType {
+        span: DUMMY_SP,
+        elem_types: types
+            .into_iter()
+            .map(|(label, ty)| TsTupleElement {
+                span: DUMMY_SP,
+                label: Some(Pat::Ident(BindingIdent {
+                    id: Ident::new_no_ctxt(label.into(), DUMMY_SP),
+                    type_ann: None,
+                })),
+                ty: Box::new(ty),
+            })
+            .collect(),
+    })
+}
+
+pub fn ts_array_type(elem_type: TsType) -> TsType {
+    // Unions need parentheses, otherwise `A | B[]` is an array of `B`
+    let elem_type = match elem_type {
+        TsType::TsUnionOrIntersectionType(_) => TsType::TsParenthesizedType(TsParenthesizedType {
+            span: DUMMY_SP,
+            type_ann: Box::new(elem_type),
+        }),
+        _ => elem_type,
+    };
+    TsType::TsArrayType(TsArrayType {
+        span: DUMMY_SP,
+        elem_type: Box::new(elem_type),
+    })
+}
+
+pub fn ts_string_literal_type<Value: Into<Atom>>(value: Value) -> TsType {
+    TsType::TsLitType(TsLitType {
+        span: DUMMY_SP,
+        lit: TsLit::Str(value.into().into()),
+    })
+}
+
+/// A template literal without expressions that TS reads back as `value`, for keys that have to match the
+/// literal type of a query exactly. Backslashes, backticks and `${` are escaped, and so are carriage returns,
+/// which TS would read as line feeds.
+pub fn ts_template_literal(value: &str) -> Expr {
+    let mut raw = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => raw.push_str("\\\\"),
+            '`' => raw.push_str("\\`"),
+            '$' if chars.peek() == Some(&'{') => raw.push_str("\\$"),
+            '\r' => raw.push_str("\\r"),
+            c => raw.push(c),
+        }
+    }
+    Expr::Tpl(Tpl {
+        span: DUMMY_SP,
+        exprs: Vec::new(),
+        quasis: vec![TplElement {
+            span: DUMMY_SP,
+            tail: true,
+            cooked: Some(value.into()),
+            raw: raw.into(),
+        }],
+    })
+}
+
+pub fn ts_bool_literal_type(value: bool) -> TsType {
+    TsType::TsLitType(TsLitType {
+        span: DUMMY_SP,
+        lit: TsLit::Bool(value.into()),
+    })
+}
+
+/// Parses a TS type expression, like `Decimal | null` or `Record<string, string>`
+pub fn parse_ts_type(source: &str) -> Option<TsType> {
+    let source = format!("type T = {source};");
+    let lexer = Lexer::new(
+        Syntax::Typescript(TsSyntax {
+            ..Default::default()
+        }),
+        Default::default(),
+        StringInput::new(&source, BytePos(0), BytePos(source.len() as u32)),
+        None,
+    );
+    let mut parser = Parser::new_from(lexer);
+    let module = parser.parse_typescript_module().ok()?;
+    let [ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(alias)))] = &module.body[..] else {
+        return None;
+    };
+    Some(*alias.type_ann.clone())
+}
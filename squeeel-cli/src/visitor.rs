@@ -1,28 +1,82 @@
-use crate::SupportedLib;
-use std::path::Path;
+use crate::{Bindings, SupportedLib};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use swc_common::{BytePos, Span};
 use swc_core::ecma::visit::Visit;
 use swc_core::ecma::visit::VisitWith as _;
-use swc_ecma_ast::CallExpr;
 use swc_ecma_ast::Module;
+use swc_ecma_ast::{
+    AwaitExpr, CallExpr, Expr, ParenExpr, Pat, TsAsExpr, TsConstAssertion, TsNonNullExpr,
+    TsSatisfiesExpr, TsTypeAssertion, VarDeclarator,
+};
 
 #[derive(Debug, Clone)]
 pub struct Query {
+    /// The query as it is passed at the call site, with the escapes of the string literal resolved
     pub query: String,
+    /// The query that is sent to the database to be described
+    pub describe_query: String,
+    pub bindings: Option<Bindings>,
     pub lib: SupportedLib,
+    pub location: QueryLocation,
+    /// The variable the result of the call is assigned to, like `getUserById` in
+    /// `const getUserById = db.prepare(...)`, which names the query unless its sql does
+    pub variable: Option<String>,
 }
 
+/// Where a query is in the source files, to point to it in warnings and errors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryLocation {
+    pub path: PathBuf,
+    /// The line of the call, counted from 1
+    pub line: usize,
+    /// The column of the call in characters, counted from 1
+    pub column: usize,
+}
+
+impl fmt::Display for QueryLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.path.display(), self.line, self.column)
+    }
+}
+
+/// The position of the first byte of a source file, as the source files are parsed. Position 0 is the dummy
+/// position of swc, so it can't be used.
+pub const SOURCE_START: BytePos = BytePos(1);
+
 struct AstVisitor<'a> {
     path: &'a Path,
+    source: &'a str,
+    /// The offset of the start of each line of the source
+    line_starts: Vec<usize>,
     libs: &'a [SupportedLib],
+    /// The variable that the call with the span is assigned to
+    assigned_call: Option<(Span, String)>,
     statements: Vec<Query>,
     errors: Vec<String>,
 }
 
 impl Visit for AstVisitor<'_> {
+    fn visit_var_declarator(&mut self, declarator: &VarDeclarator) {
+        if let (Pat::Ident(ident), Some(call_expr)) = (
+            &declarator.name,
+            declarator.init.as_deref().and_then(assigned_call_expr),
+        ) {
+            self.assigned_call = Some((call_expr.span, ident.id.sym.to_string()));
+        }
+        declarator.visit_children_with(self);
+    }
+
     fn visit_call_expr(&mut self, call_expr: &CallExpr) {
         let mut libs_that_detected_a_query = Vec::new();
+        let location = self.location(call_expr.span);
+        let variable = self
+            .assigned_call
+            .take_if(|(span, _)| *span == call_expr.span)
+            .map(|(_, variable)| variable);
         for lib in self.libs {
-            if let Some(statement) = lib.parse_call_expr(call_expr) {
+            if let Some(mut statement) = lib.parse_call_expr(call_expr, &location) {
+                statement.variable = variable.clone();
                 self.statements.push(statement);
                 libs_that_detected_a_query.push(lib);
             }
@@ -32,36 +86,68 @@ impl Visit for AstVisitor<'_> {
         }
         if libs_that_detected_a_query.len() > 1 {
             self.errors.push(format!(
-                "Multiple libs ({}) detected the same query in {}:{:?}.",
+                "Multiple libs ({}) detected the same query in {location}.",
                 libs_that_detected_a_query
                     .iter()
                     .map(|l| l.to_string())
                     .collect::<Vec<_>>()
                     .join(", "),
-                self.path.to_string_lossy(),
-                call_expr.span
             ));
         }
     }
 }
 
 impl<'a> AstVisitor<'a> {
-    pub fn new(libs: &'a [SupportedLib], path: &'a Path) -> Self {
+    pub fn new(libs: &'a [SupportedLib], path: &'a Path, source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
         Self {
             path,
+            source,
+            line_starts,
             libs,
+            assigned_call: None,
             statements: Vec::new(),
             errors: Vec::new(),
         }
     }
+
+    fn location(&self, span: Span) -> QueryLocation {
+        let offset = (span.lo.0.saturating_sub(SOURCE_START.0) as usize).min(self.source.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let line_start = self.line_starts[line - 1];
+        QueryLocation {
+            path: self.path.to_path_buf(),
+            line,
+            column: self.source[line_start..offset].chars().count() + 1,
+        }
+    }
+}
+
+/// The call whose result is assigned, looking through `await`, parentheses and type assertions, like in
+/// `const users = (await client.query(...)) as Result`
+fn assigned_call_expr(expr: &Expr) -> Option<&CallExpr> {
+    match expr {
+        Expr::Call(call_expr) => Some(call_expr),
+        Expr::Await(AwaitExpr { arg, .. })
+        | Expr::Paren(ParenExpr { expr: arg, .. })
+        | Expr::TsAs(TsAsExpr { expr: arg, .. })
+        | Expr::TsNonNull(TsNonNullExpr { expr: arg, .. })
+        | Expr::TsSatisfies(TsSatisfiesExpr { expr: arg, .. })
+        | Expr::TsTypeAssertion(TsTypeAssertion { expr: arg, .. })
+        | Expr::TsConstAssertion(TsConstAssertion { expr: arg, .. }) => assigned_call_expr(arg),
+        _ => None,
+    }
 }
 
 pub fn visit_ast(
     supported_libs: &[SupportedLib],
     module: &Module,
     path: &Path,
+    source: &str,
 ) -> Result<Vec<Query>, Vec<String>> {
-    let mut ast_visitor = AstVisitor::new(supported_libs, path);
+    let mut ast_visitor = AstVisitor::new(supported_libs, path, source);
     ast_visitor.visit_module(module);
     if !ast_visitor.errors.is_empty() {
         return Err(ast_visitor.errors);